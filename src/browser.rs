@@ -0,0 +1,50 @@
+use crate::request::HttpResponse;
+use reqwest::header::CONTENT_TYPE;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File extension matching the response Content-Type, so the system picks
+/// the right application.
+fn extension(response: &HttpResponse) -> &'static str {
+    let mime = response
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "text/html" | "application/xhtml+xml" => "html",
+        "image/svg+xml" => "svg",
+        "application/pdf" => "pdf",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "text/css" => "css",
+        "text/javascript" | "application/javascript" => "js",
+        "text/csv" => "csv",
+        m if m.contains("json") => "json",
+        m if m.contains("xml") => "xml",
+        m if m.starts_with("text/") => "txt",
+        _ => "bin",
+    }
+}
+
+/// Writes the response body to a temporary file and opens it with the
+/// default application for its type.
+pub fn open_response(response: &HttpResponse) -> Result<PathBuf, String> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!(
+        "patch-lite-response-{stamp}.{}",
+        extension(response)
+    ));
+    std::fs::write(&path, &response.bytes)
+        .map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    open::that_detached(&path).map_err(|e| format!("Could not open {}: {e}", path.display()))?;
+    Ok(path)
+}
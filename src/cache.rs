@@ -0,0 +1,56 @@
+use crate::request::{HttpMethod, HttpRequest, HttpResponse};
+use reqwest::StatusCode;
+use reqwest::header::{IF_MODIFIED_SINCE, LAST_MODIFIED};
+use std::collections::HashMap;
+
+/// Last response per URL that carried a `Last-Modified` date, used to send
+/// conditional GETs and to fill in the body of a `304 Not Modified`.
+#[derive(Debug, Default)]
+pub struct ConditionalCache {
+    entries: HashMap<String, HttpResponse>,
+}
+
+impl ConditionalCache {
+    /// Adds `If-Modified-Since` to a GET of a cached URL, unless the request
+    /// sets the header itself.
+    pub fn prepare(&self, request: &mut HttpRequest) {
+        if request.method != Some(HttpMethod::GET)
+            || request.headers.contains_key(IF_MODIFIED_SINCE)
+        {
+            return;
+        }
+        if let Some(last_modified) = self
+            .entries
+            .get(&request.url)
+            .and_then(|cached| cached.headers.get(LAST_MODIFIED))
+        {
+            request
+                .headers
+                .insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+    }
+
+    /// Remembers successful responses with `Last-Modified`. A 304 gets the
+    /// cached body (keeping its own status and headers); the flag tells
+    /// whether that happened.
+    pub fn resolve(&mut self, url: &str, response: HttpResponse) -> (HttpResponse, bool) {
+        if response.status == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = self.entries.get(url) {
+                let merged = HttpResponse {
+                    body: cached.body.clone(),
+                    bytes: cached.bytes.clone(),
+                    content_encoding: None,
+                    ..response
+                };
+                return (merged, true);
+            }
+        } else if response.status.is_success() && response.headers.contains_key(LAST_MODIFIED) {
+            self.entries.insert(url.to_string(), response.clone());
+        }
+        (response, false)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,227 @@
+use serde_json::Value;
+use std::collections::HashSet;
+
+// Inferred type of a JSON node. Shapes of array elements and of the same
+// field in different objects are merged, so the result covers every sample.
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Unknown,
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Mixed,
+    Optional(Box<Shape>),
+    Array(Box<Shape>),
+    Object(Vec<(String, Shape)>),
+}
+
+fn shape_of(value: &Value) -> Shape {
+    match value {
+        Value::Null => Shape::Null,
+        Value::Bool(_) => Shape::Bool,
+        Value::Number(n) if n.is_f64() => Shape::Float,
+        Value::Number(_) => Shape::Int,
+        Value::String(_) => Shape::Str,
+        Value::Array(items) => Shape::Array(Box::new(
+            items.iter().map(shape_of).fold(Shape::Unknown, merge),
+        )),
+        Value::Object(map) => Shape::Object(
+            map.iter()
+                .map(|(key, v)| (key.clone(), shape_of(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn optional(shape: Shape) -> Shape {
+    match shape {
+        Shape::Optional(_) | Shape::Null => shape,
+        other => Shape::Optional(Box::new(other)),
+    }
+}
+
+fn merge(a: Shape, b: Shape) -> Shape {
+    match (a, b) {
+        (Shape::Unknown, x) | (x, Shape::Unknown) => x,
+        (Shape::Null, Shape::Null) => Shape::Null,
+        (Shape::Null, x) | (x, Shape::Null) => optional(x),
+        (Shape::Optional(a), b) | (b, Shape::Optional(a)) => optional(merge(*a, b)),
+        (Shape::Int, Shape::Float) | (Shape::Float, Shape::Int) => Shape::Float,
+        (Shape::Array(a), Shape::Array(b)) => Shape::Array(Box::new(merge(*a, *b))),
+        (Shape::Object(a), Shape::Object(b)) => Shape::Object(merge_fields(a, b)),
+        (a, b) if a == b => a,
+        _ => Shape::Mixed,
+    }
+}
+
+// Fields that are missing from some of the objects become optional.
+fn merge_fields(a: Vec<(String, Shape)>, mut b: Vec<(String, Shape)>) -> Vec<(String, Shape)> {
+    let mut merged = Vec::new();
+    for (name, shape) in a {
+        match b.iter().position(|(other, _)| *other == name) {
+            Some(i) => {
+                let (_, other) = b.remove(i);
+                merged.push((name, merge(shape, other)));
+            }
+            None => merged.push((name, optional(shape))),
+        }
+    }
+    merged.extend(b.into_iter().map(|(name, shape)| (name, optional(shape))));
+    merged
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+fn snake_case(key: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            prev_lower = true;
+        } else {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+fn field_ident(key: &str) -> String {
+    let name = snake_case(key);
+    if name.is_empty() {
+        "field".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if matches!(name.as_str(), "self" | "super" | "crate") {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
+
+fn pascal_case(hint: &str) -> String {
+    let name: String = snake_case(hint)
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    if name.is_empty() {
+        "Root".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("T{name}")
+    } else {
+        name
+    }
+}
+
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if name.ends_with("ss") || name.ends_with("us") {
+        name.to_string()
+    } else {
+        name.strip_suffix('s').unwrap_or(name).to_string()
+    }
+}
+
+#[derive(Default)]
+struct Generator {
+    used_names: HashSet<String>,
+    definitions: Vec<String>,
+}
+
+impl Generator {
+    fn type_name(&mut self, hint: &str) -> String {
+        let base = pascal_case(hint);
+        let mut name = base.clone();
+        let mut n = 2;
+        while !self.used_names.insert(name.clone()) {
+            name = format!("{base}{n}");
+            n += 1;
+        }
+        name
+    }
+
+    fn rust_type(&mut self, shape: &Shape, hint: &str) -> String {
+        match shape {
+            Shape::Unknown | Shape::Mixed => "serde_json::Value".to_string(),
+            Shape::Null => "Option<serde_json::Value>".to_string(),
+            Shape::Bool => "bool".to_string(),
+            Shape::Int => "i64".to_string(),
+            Shape::Float => "f64".to_string(),
+            Shape::Str => "String".to_string(),
+            Shape::Optional(inner) => format!("Option<{}>", self.rust_type(inner, hint)),
+            Shape::Array(inner) => format!("Vec<{}>", self.rust_type(inner, &singular(hint))),
+            Shape::Object(fields) => self.emit_struct(fields, hint),
+        }
+    }
+
+    fn emit_struct(&mut self, fields: &[(String, Shape)], hint: &str) -> String {
+        let name = self.type_name(hint);
+        // Reserve the slot first so parents are printed before their children.
+        let slot = self.definitions.len();
+        self.definitions.push(String::new());
+
+        let mut out =
+            format!("#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {name} {{\n");
+        let mut used_fields = HashSet::new();
+        for (key, shape) in fields {
+            let ty = self.rust_type(shape, key);
+            let base = field_ident(key);
+            let mut field = base.clone();
+            let mut n = 2;
+            while !used_fields.insert(field.clone()) {
+                field = format!("{base}_{n}");
+                n += 1;
+            }
+            if field.trim_start_matches("r#") != key {
+                out.push_str(&format!("    #[serde(rename = {key:?})]\n"));
+            }
+            out.push_str(&format!("    pub {field}: {ty},\n"));
+        }
+        out.push_str("}\n");
+
+        self.definitions[slot] = out;
+        name
+    }
+}
+
+/// Generates serde-ready Rust type definitions that can deserialize `value`.
+pub fn gen_rust_types(value: &Value, root_name: &str) -> String {
+    let mut generator = Generator::default();
+    let shape = shape_of(value);
+    let root_type = generator.rust_type(&shape, root_name);
+
+    let mut out = String::from("use serde::{Deserialize, Serialize};\n\n");
+    if !matches!(shape, Shape::Object(_)) {
+        let alias = generator.type_name(&format!("{root_name}Response"));
+        out.push_str(&format!("pub type {alias} = {root_type};\n\n"));
+    }
+    out.push_str(&generator.definitions.join("\n"));
+    out
+}
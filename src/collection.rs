@@ -0,0 +1,249 @@
+use crate::request::{HttpClient, HttpMethod, HttpRequest, retry_after};
+use iced::futures::{StreamExt, stream};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const COLLECTION_FILE: &str = "collection.json";
+pub const SNIPPETS_FILE: &str = "snippets.json";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedRequest {
+    /// Stable key of the request's keychain entry; names are not unique.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub request: HttpRequest,
+    /// Shown in the quick-access bar above the request.
+    #[serde(default)]
+    pub starred: bool,
+}
+
+impl SavedRequest {
+    pub fn new(name: String, request: HttpRequest) -> Self {
+        SavedRequest {
+            id: new_id(),
+            name,
+            request,
+            starred: false,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        if self.name.trim().is_empty() {
+            &self.request.url
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// A fresh id for a saved request: the creation time plus a counter, so
+/// requests created in the same instant still differ.
+fn new_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("{nanos:x}-{:x}", COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Gives a new id to requests loaded without one, or sharing one (a
+/// hand-edited file). Returns whether anything changed.
+pub fn ensure_ids(collection: &mut [SavedRequest]) -> bool {
+    let mut seen = HashSet::new();
+    let mut changed = false;
+    for saved in collection {
+        if saved.id.is_empty() || !seen.insert(saved.id.clone()) {
+            saved.id = new_id();
+            seen.insert(saved.id.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// A named body skeleton that can be inserted into the body editor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub text: String,
+}
+
+/// One entry of a batch import file. `body` may be a string or any JSON
+/// value, which is stored pretty-printed.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchEntry {
+    #[serde(default)]
+    name: String,
+    method: HttpMethod,
+    url: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: Option<Value>,
+}
+
+impl BatchEntry {
+    fn into_saved(self) -> Result<SavedRequest, String> {
+        if self.url.trim().is_empty() {
+            return Err("missing url".to_string());
+        }
+        let mut headers = HeaderMap::new();
+        for (key, value) in &self.headers {
+            let name = key
+                .parse::<HeaderName>()
+                .map_err(|_| format!("invalid header name '{key}'"))?;
+            let value = value
+                .parse::<HeaderValue>()
+                .map_err(|_| format!("invalid value for header '{key}'"))?;
+            headers.append(name, value);
+        }
+        let body = match self.body {
+            None | Some(Value::Null) => None,
+            Some(Value::String(body)) => Some(body),
+            Some(value) => serde_json::to_string_pretty(&value).ok(),
+        };
+        Ok(SavedRequest::new(
+            self.name,
+            HttpRequest {
+                method: Some(self.method),
+                url: self.url,
+                body,
+                headers,
+                ..HttpRequest::default()
+            },
+        ))
+    }
+}
+
+/// Parses a JSON array of `{name, method, url, headers, body}` objects.
+/// Entries that fail validation are skipped and described in the second
+/// list ("#3: missing field `url`").
+pub fn import_batch(text: &str) -> Result<(Vec<SavedRequest>, Vec<String>), String> {
+    let entries: Vec<Value> =
+        serde_json::from_str(text).map_err(|e| format!("Expected a JSON array: {e}"))?;
+    let mut imported = Vec::new();
+    let mut failures = Vec::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        match serde_json::from_value::<BatchEntry>(entry)
+            .map_err(|e| e.to_string())
+            .and_then(BatchEntry::into_saved)
+        {
+            Ok(saved) => imported.push(saved),
+            Err(e) => failures.push(format!("#{}: {e}", i + 1)),
+        }
+    }
+    Ok((imported, failures))
+}
+
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    pub name: String,
+    pub outcome: Result<u16, String>,
+    /// When the request was sent, relative to the start of the run.
+    pub started: Duration,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RunSummary {
+    pub results: Vec<RunResult>,
+    pub total: Duration,
+}
+
+impl RunSummary {
+    /// Number of responses whose status falls in the given class (2 for 2xx...).
+    pub fn count_class(&self, class: u16) -> usize {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.outcome, Ok(status) if status / 100 == class))
+            .count()
+    }
+
+    pub fn count_errors(&self) -> usize {
+        self.results.iter().filter(|r| r.outcome.is_err()).count()
+    }
+}
+
+async fn run_one(saved: SavedRequest, client: &HttpClient, run_start: Instant) -> RunResult {
+    attempt(&saved, client, run_start).await.0
+}
+
+/// Sends `saved` once; also returns the `Retry-After` delay of a 429.
+async fn attempt(
+    saved: &SavedRequest,
+    client: &HttpClient,
+    run_start: Instant,
+) -> (RunResult, Option<Duration>) {
+    let begun = Instant::now();
+    let response = saved.request.send(client).await;
+    let wait = response
+        .as_ref()
+        .ok()
+        .filter(|response| response.status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+        .and_then(|response| retry_after(&response.headers));
+    let outcome = response
+        .map(|response| response.status.as_u16())
+        .map_err(|e| e.describe());
+    let result = RunResult {
+        name: saved.label().to_string(),
+        outcome,
+        started: begun - run_start,
+        elapsed: begun.elapsed(),
+    };
+    (result, wait)
+}
+
+/// Longest `Retry-After` the sequential runner waits for before retrying.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Sends the requests concurrently, at most `limit` at a time, and waits
+/// for all of them to finish. Results keep the collection order.
+pub async fn send_all(requests: Vec<SavedRequest>, client: HttpClient, limit: usize) -> RunSummary {
+    let started = Instant::now();
+    let client = &client;
+    let results = stream::iter(requests)
+        .map(|saved| run_one(saved, client, started))
+        .buffered(limit.max(1))
+        .collect()
+        .await;
+
+    RunSummary {
+        results,
+        total: started.elapsed(),
+    }
+}
+
+/// Sends the requests one after another, in collection order, waiting
+/// `delay` between them. A 429 with `Retry-After` is retried once after the
+/// requested wait (up to `MAX_RETRY_AFTER`).
+pub async fn send_sequential(
+    requests: Vec<SavedRequest>,
+    client: HttpClient,
+    delay: Duration,
+) -> RunSummary {
+    let started = Instant::now();
+    let mut results = Vec::with_capacity(requests.len());
+    for (i, saved) in requests.iter().enumerate() {
+        if i > 0 && !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        let (mut result, wait) = attempt(saved, &client, started).await;
+        if let Some(wait) = wait.filter(|wait| *wait <= MAX_RETRY_AFTER) {
+            tokio::time::sleep(wait).await;
+            result = attempt(saved, &client, started).await.0;
+        }
+        results.push(result);
+    }
+
+    RunSummary {
+        results,
+        total: started.elapsed(),
+    }
+}
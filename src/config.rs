@@ -0,0 +1,27 @@
+use crate::env::Environments;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const CONFIG_EXPORT_FILE: &str = "patch-lite-config.json";
+
+/// Everything under Settings in one file, for moving between machines.
+/// Collections and snippets are not included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub settings: Settings,
+    pub environments: Environments,
+}
+
+pub fn export(path: &Path, config: &AppConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+pub fn import(path: &Path) -> Result<AppConfig, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("{} is not a PatchLite config: {e}", path.display()))
+}
@@ -0,0 +1,189 @@
+use crate::json_tools::flatten_json;
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Above this many line pairs the LCS table gets too big; the diff then just
+/// shows everything as removed and re-added.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Line diff of `old` against `new` based on the longest common subsequence.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|l| DiffLine::Removed(l.to_string()))
+            .chain(new.iter().map(|l| DiffLine::Added(l.to_string())))
+            .collect();
+    }
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            out.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    out.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    out
+}
+
+/// Pretty-prints `src` with sorted keys (`Value` keeps objects in a
+/// BTreeMap), so formatting and key order do not show up in a diff.
+pub fn normalize_json(src: &str) -> Result<String, serde_json::Error> {
+    serde_json::from_str::<Value>(src).and_then(|v| serde_json::to_string_pretty(&v))
+}
+
+/// Splits an ignore list ("data.created_at, items.*.id") into paths. Array
+/// indices may be written as `items[0]` or `items.0`.
+pub fn parse_ignore_paths(src: &str) -> Vec<Vec<String>> {
+    src.split([',', '\n'])
+        .map(|path| {
+            path.trim()
+                .replace('[', ".")
+                .replace(']', "")
+                .split('.')
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| segment.trim_matches('"').to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Removes whatever `path` points to from `value`; `*` matches every key or
+/// index at its level.
+pub fn remove_path(value: &mut Value, path: &[String]) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    let wildcard = first == "*";
+    match value {
+        Value::Object(map) if rest.is_empty() => {
+            if wildcard {
+                map.clear();
+            } else {
+                map.remove(first);
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if wildcard || key == first {
+                    remove_path(child, rest);
+                }
+            }
+        }
+        Value::Array(items) if rest.is_empty() => {
+            if wildcard {
+                items.clear();
+            } else if let Some(index) = first.parse::<usize>().ok().filter(|i| *i < items.len()) {
+                items.remove(index);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                if wildcard || first.parse() == Ok(index) {
+                    remove_path(child, rest);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Diffs two bodies after dropping the ignored paths. Bodies that are not
+/// JSON are compared as plain text.
+pub fn diff_ignoring(old: &str, new: &str, ignore: &[Vec<String>]) -> Vec<DiffLine> {
+    let prepare = |src: &str| -> Option<String> {
+        let mut value = serde_json::from_str::<Value>(src).ok()?;
+        for path in ignore {
+            remove_path(&mut value, path);
+        }
+        serde_json::to_string_pretty(&value).ok()
+    };
+    match (prepare(old), prepare(new)) {
+        (Some(old), Some(new)) => line_diff(&old, &new),
+        _ => line_diff(old, new),
+    }
+}
+
+/// A leaf that differs between two JSON documents. `old` is `None` for a
+/// field that appeared, `new` for one that went away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Leaf fields (as flattened paths, e.g. `job.status`) whose value differs
+/// between `old` and `new`, in path order.
+pub fn changed_fields(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let old = flatten_json(old);
+    let new = flatten_json(new);
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    // Both lists are sorted by path, so a merge walk pairs them up.
+    while i < old.len() || j < new.len() {
+        match (old.get(i), new.get(j)) {
+            (Some((old_path, old_value)), Some((new_path, new_value))) if old_path == new_path => {
+                if old_value != new_value {
+                    out.push(FieldChange {
+                        path: old_path.clone(),
+                        old: Some(old_value.clone()),
+                        new: Some(new_value.clone()),
+                    });
+                }
+                i += 1;
+                j += 1;
+            }
+            (Some((old_path, old_value)), next) if next.is_none_or(|(p, _)| old_path < p) => {
+                out.push(FieldChange {
+                    path: old_path.clone(),
+                    old: Some(old_value.clone()),
+                    new: None,
+                });
+                i += 1;
+            }
+            (_, Some((new_path, new_value))) => {
+                out.push(FieldChange {
+                    path: new_path.clone(),
+                    old: None,
+                    new: Some(new_value.clone()),
+                });
+                j += 1;
+            }
+            (_, None) => break,
+        }
+    }
+    out
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Environment variables, referenced in requests as `{{name}}`.
+pub const ENV_FILE: &str = "environments.json";
+
+/// Named variable sets (Dev, Staging, Prod...), one of them active.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Environments {
+    pub active: Option<String>,
+    pub sets: BTreeMap<String, Vec<(String, String)>>,
+}
+
+impl Environments {
+    /// Variables of the active environment; empty when none is selected.
+    pub fn active_vars(&self) -> &[(String, String)] {
+        self.active
+            .as_ref()
+            .and_then(|name| self.sets.get(name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn active_vars_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        let name = self.active.as_ref()?;
+        self.sets.get_mut(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.sets.keys().cloned().collect()
+    }
+
+    /// Adds an empty environment (if new) and makes it active.
+    pub fn add(&mut self, name: String) {
+        self.sets.entry(name.clone()).or_default();
+        self.active = Some(name);
+    }
+
+    /// Removes the active environment and activates the first remaining one.
+    pub fn remove_active(&mut self) {
+        if let Some(name) = self.active.take() {
+            self.sets.remove(&name);
+        }
+        self.active = self.sets.keys().next().cloned();
+    }
+}
+
+/// The token inserted into inputs for variable `name`.
+pub fn token(name: &str) -> String {
+    format!("{{{{{name}}}}}")
+}
+
+/// Replaces every `{{name}}` with its value. Unknown names and unclosed
+/// braces are left as typed so they stay visible in the sent request.
+pub fn substitute(text: &str, vars: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let name = after[..end].trim();
+        match vars.iter().find(|(key, _)| key.trim() == name) {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,151 @@
+use crate::request::{
+    Auth, HttpClient, HttpMethod, HttpRequest, HttpResponse, is_sensitive_header,
+};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
+
+/// Keychain entry holding the GitHub personal access token.
+pub const TOKEN_ENTRY: &str = "github-gist-token";
+
+const API_URL: &str = "https://api.github.com/gists";
+
+const REDACTED: &str = "[redacted]";
+
+/// Query parameters whose values never leave the machine: the sensitive
+/// header names plus the usual spellings of keys and signatures.
+fn is_sensitive_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    is_sensitive_header(&name)
+        || name.contains("key")
+        || name.contains("password")
+        || name.contains("auth")
+        || name.contains("session")
+        || name == "sig"
+        || name == "signature"
+}
+
+/// `url` with the `user:pass@` part and sensitive query values replaced.
+/// Works on the text so URLs that don't parse are still redacted.
+pub fn redact_url(url: &str) -> String {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let (url, query) = match url.split_once('?') {
+        Some((url, query)) => (url, Some(query)),
+        None => (url, None),
+    };
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (format!("{scheme}://"), rest),
+        None => (String::new(), url),
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    let authority = match authority.rsplit_once('@') {
+        Some((_, host)) => format!("{REDACTED}@{host}"),
+        None => authority.to_string(),
+    };
+
+    let mut out = format!("{scheme}{authority}{path}");
+    if let Some(query) = query {
+        let pairs: Vec<String> = query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if is_sensitive_param(name) => format!("{name}={REDACTED}"),
+                _ => pair.to_string(),
+            })
+            .collect();
+        out.push('?');
+        out.push_str(&pairs.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+fn redact_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut redacted = headers.clone();
+    for (name, value) in redacted.iter_mut() {
+        if is_sensitive_header(name.as_str()) {
+            *value = HeaderValue::from_static(REDACTED);
+        }
+    }
+    redacted
+}
+
+/// Markdown describing the exchange: the request as curl, then the
+/// response. Credentials, sensitive headers, URL userinfo and sensitive
+/// query values are replaced by `[redacted]`; bodies are kept verbatim.
+pub fn exchange_markdown(request: &HttpRequest, response: &HttpResponse) -> String {
+    let request = HttpRequest {
+        url: redact_url(&request.url),
+        token: if request.token.is_empty() {
+            String::new()
+        } else {
+            REDACTED.to_string()
+        },
+        password: if request.password.is_empty() {
+            String::new()
+        } else {
+            REDACTED.to_string()
+        },
+        headers: redact_headers(&request.headers),
+        ..request.clone()
+    };
+    let headers: String = redact_headers(&response.headers)
+        .iter()
+        .map(|(name, value)| format!("{name}: {}\n", String::from_utf8_lossy(value.as_bytes())))
+        .collect();
+    format!(
+        "```sh\n{}\n```\n\n```http\nHTTP {}\n{headers}\n{}\n```\n",
+        request.to_curl(),
+        response.status,
+        response.body
+    )
+}
+
+/// Creates a secret (unlisted) gist with `content` and returns its URL.
+pub async fn upload(
+    token: String,
+    description: String,
+    content: String,
+    client: HttpClient,
+) -> Result<String, String> {
+    let payload = serde_json::json!({
+        "description": description,
+        "public": false,
+        "files": { "exchange.md": { "content": content } },
+    });
+    let mut request = HttpRequest {
+        method: Some(HttpMethod::POST),
+        url: API_URL.to_string(),
+        body: Some(payload.to_string()),
+        auth: Auth::Bearer,
+        token,
+        manual_content_type: true,
+        ..HttpRequest::default()
+    };
+    request
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    request.headers.insert(
+        ACCEPT,
+        HeaderValue::from_static("application/vnd.github+json"),
+    );
+    request
+        .headers
+        .insert(USER_AGENT, HeaderValue::from_static("PatchLite"));
+
+    let response = request.send(&client).await.map_err(|e| e.describe())?;
+    if !response.status.is_success() {
+        return Err(format!(
+            "GitHub answered {}: {}",
+            response.status, response.body
+        ));
+    }
+    serde_json::from_str::<serde_json::Value>(&response.body)
+        .ok()
+        .and_then(|value| value.get("html_url")?.as_str().map(str::to_string))
+        .ok_or_else(|| "GitHub did not return the gist URL".to_string())
+}
@@ -0,0 +1,39 @@
+/// Bytes shown in the hex view; the rest is summarized.
+pub const MAX_HEXDUMP_BYTES: usize = 64 * 1024;
+
+/// Classic hexdump: offset, 16 bytes in hex and an ASCII gutter.
+///
+/// ```text
+/// 00000000  7b 22 69 64 22 3a 20 31  7d 0a                    |{"id": 1}.|
+/// ```
+pub fn hexdump(bytes: &[u8]) -> String {
+    let shown = &bytes[..bytes.len().min(MAX_HEXDUMP_BYTES)];
+    let mut out = String::with_capacity(shown.len() * 4 + 80);
+
+    for (line, chunk) in shown.chunks(16).enumerate() {
+        out.push_str(&format!("{:08x}  ", line * 16));
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(b) => out.push_str(&format!("{b:02x} ")),
+                None => out.push_str("   "),
+            }
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+    }
+
+    if bytes.len() > shown.len() {
+        out.push_str(&format!("... {} more bytes\n", bytes.len() - shown.len()));
+    }
+    out
+}
@@ -0,0 +1,53 @@
+use crate::request::{HttpRequest, is_sensitive_header};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const HISTORY_FILE: &str = "history.json";
+
+/// New entries are written out at most this often (and on exit), so
+/// polling doesn't rewrite the file on every response.
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A request as it was edited (variables still as `{{name}}`), with the
+/// outcome.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub request: HttpRequest,
+    /// Status code, or `None` when the send failed.
+    pub status: Option<u16>,
+    /// Local time of the send, e.g. `2024-05-01 13:37:00`.
+    pub sent_at: String,
+}
+
+/// Copy of `request` fit for the history file: no token, password or
+/// credential-carrying header.
+pub fn scrubbed(request: &HttpRequest) -> HttpRequest {
+    let mut request = HttpRequest {
+        token: String::new(),
+        password: String::new(),
+        ..request.clone()
+    };
+    let sensitive: Vec<_> = request
+        .headers
+        .keys()
+        .filter(|name| is_sensitive_header(name.as_str()))
+        .cloned()
+        .collect();
+    for name in sensitive {
+        request.headers.remove(name);
+    }
+    request
+}
+
+/// Appends `entry` and drops the oldest entries beyond `max`.
+pub fn push(history: &mut Vec<HistoryEntry>, entry: HistoryEntry, max: usize) {
+    history.push(entry);
+    trim(history, max);
+}
+
+/// Keeps only the newest `max` entries.
+pub fn trim(history: &mut Vec<HistoryEntry>, max: usize) {
+    if history.len() > max {
+        history.drain(..history.len() - max);
+    }
+}
@@ -1,6 +1,8 @@
 use iced::widget::text::{Rich, Span};
 use iced::{Color, Font};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use serde_json::ser::PrettyFormatter;
 
 /// Tema de cores (estilo "Postman-ish").
 #[derive(Clone, Copy)]
@@ -28,11 +30,93 @@ impl Default for Theme {
     }
 }
 
-/// Converte um `&str` contendo JSON em `Rich<'static, ()>`.
+/// Indentação usada ao formatar JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Indent {
+    #[default]
+    Two,
+    Four,
+    Tab,
+}
+
+impl Indent {
+    pub const ALL: [Indent; 3] = [Indent::Two, Indent::Four, Indent::Tab];
+
+    fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Indent::Two => b"  ",
+            Indent::Four => b"    ",
+            Indent::Tab => b"\t",
+        }
+    }
+}
+
+impl std::fmt::Display for Indent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Indent::Two => write!(f, "2 spaces"),
+            Indent::Four => write!(f, "4 spaces"),
+            Indent::Tab => write!(f, "Tab"),
+        }
+    }
+}
+
+/// Fonte do JSON destacado e dos editores de texto.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorFont {
+    pub size: f32,
+    pub monospace: bool,
+}
+
+impl Default for EditorFont {
+    fn default() -> Self {
+        Self {
+            size: 14.0,
+            monospace: true,
+        }
+    }
+}
+
+impl EditorFont {
+    pub const MIN_SIZE: f32 = 8.0;
+    pub const MAX_SIZE: f32 = 40.0;
+
+    pub fn font(&self) -> Font {
+        if self.monospace {
+            Font::MONOSPACE
+        } else {
+            Font::DEFAULT
+        }
+    }
+
+    /// Aumenta (ou diminui, com `delta` negativo) o tamanho, dentro dos limites.
+    pub fn zoomed(self, delta: f32) -> Self {
+        Self {
+            size: (self.size + delta).clamp(Self::MIN_SIZE, Self::MAX_SIZE),
+            ..self
+        }
+    }
+}
+
+/// Igual a `serde_json::to_string_pretty`, mas com a indentação escolhida.
+pub fn to_string_indented(value: &Value, indent: Indent) -> Option<String> {
+    let mut out = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value.serialize(&mut serializer).ok()?;
+    String::from_utf8(out).ok()
+}
+
+/// Converte um `&str` contendo JSON em `Rich<'static, Link>`.
 /// Se o JSON for inválido, mostra um aviso + conteúdo original sem highlight.
-pub fn rich_json_str(src: &str) -> Rich<'static, ()> {
+pub fn rich_json_str<Link: Clone + 'static>(
+    src: &str,
+    indent: Indent,
+    font: EditorFont,
+) -> Rich<'static, Link> {
     match serde_json::from_str::<Value>(src) {
-        Ok(v) => rich_json_value(&v),
+        Ok(v) => rich_json_value(&v, indent, font),
         Err(e) => {
             let mut spans = Vec::new();
             spans.push(
@@ -40,34 +124,182 @@ pub fn rich_json_str(src: &str) -> Rich<'static, ()> {
                     .color(Color::from_rgb8(255, 100, 100)),
             );
             spans.push(Span::new(src.to_owned()).color(Theme::default().default));
-            Rich::with_spans(spans).font(Font::MONOSPACE).size(14)
+            Rich::with_spans(spans).font(font.font()).size(font.size)
         }
     }
 }
 
+/// Converte a coluna de um `serde_json::Error` (1-based, contada em bytes)
+/// no índice 0-based do caractere dentro de `text`.
+pub fn char_column(text: &str, column: usize) -> usize {
+    let mut byte = column.saturating_sub(1).min(text.len());
+    while !text.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    text[..byte].chars().count()
+}
+
+/// Caracteres mostrados de cada lado da coluna do erro em linhas longas.
+const CARET_CONTEXT: usize = 40;
+
+/// A linha `line` de `src` (1-based, como em `serde_json::Error`) com um `^`
+/// embaixo da coluna `column` (em bytes, também como no erro). Linhas longas (HTML minificado, por exemplo)
+/// são cortadas em volta da coluna.
+pub fn error_caret(src: &str, line: usize, column: usize) -> String {
+    let Some(text) = src.lines().nth(line.saturating_sub(1)) else {
+        return String::new();
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let column = char_column(text, column);
+    let start = column.saturating_sub(CARET_CONTEXT);
+    let end = (column + CARET_CONTEXT).min(chars.len());
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < chars.len() { "…" } else { "" };
+    let snippet: String = chars[start..end]
+        .iter()
+        .map(|&c| if c == '\t' { ' ' } else { c })
+        .collect();
+    let gutter = format!("{line} | ");
+    format!(
+        "{gutter}{prefix}{snippet}{suffix}\n{}^",
+        " ".repeat(gutter.chars().count() + prefix.chars().count() + column - start)
+    )
+}
+
 /// Versão para `serde_json::Value`.
-pub fn rich_json_value(value: &Value) -> Rich<'static, ()> {
-    let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| "<invalid json>".into());
-    rich_json_pretty_str(&pretty, Theme::default())
+pub fn rich_json_value<Link: Clone + 'static>(
+    value: &Value,
+    indent: Indent,
+    font: EditorFont,
+) -> Rich<'static, Link> {
+    let pretty = to_string_indented(value, indent).unwrap_or_else(|| "<invalid json>".into());
+    rich_json_pretty_str(&pretty, Theme::default(), font)
 }
 
 /// Mesmo que `rich_json_str`, mas recebendo:
 /// - o JSON já "pretty" (com quebras e indentação)
 /// - um tema customizável
-pub fn rich_json_pretty_str(pretty_src: &str, theme: Theme) -> Rich<'static, ()> {
+/// - a fonte escolhida nas configurações
+pub fn rich_json_pretty_str<Link: Clone + 'static>(
+    pretty_src: &str,
+    theme: Theme,
+    font: EditorFont,
+) -> Rich<'static, Link> {
     let spans = json_to_spans(pretty_src, theme);
-    Rich::with_spans(spans).font(Font::MONOSPACE).size(14)
+    Rich::with_spans(spans).font(font.font()).size(font.size)
 }
 
 /// Útil para logs/clipboard: apenas identa (sem cores).
-pub fn pretty_json_str(src: &str) -> String {
+pub fn pretty_json_str(src: &str, indent: Indent) -> String {
     match serde_json::from_str::<Value>(src) {
-        Ok(v) => serde_json::to_string_pretty(&v).unwrap_or_else(|_| src.to_string()),
+        Ok(v) => to_string_indented(&v, indent).unwrap_or_else(|| src.to_string()),
         Err(_) => src.to_string(),
     }
 }
 
-fn json_to_spans(src: &str, th: Theme) -> Vec<Span<'static>> {
+/// Quebra as linhas de `src` na coluna `width`, preferindo cortar entre
+/// tokens (depois de espaço, `,` ou `:`, ou entre strings) para não partir
+/// um token colorido no meio. Tokens maiores que `width` são cortados.
+/// As linhas de continuação mantêm a indentação da linha original.
+pub fn wrap_at_column(src: &str, width: usize) -> String {
+    if width == 0 {
+        return src.to_string();
+    }
+
+    let mut out = String::with_capacity(src.len());
+    for (n, line) in src.lines().enumerate() {
+        if n > 0 {
+            out.push('\n');
+        }
+        if line.chars().count() <= width {
+            out.push_str(line);
+            continue;
+        }
+
+        let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+        // Sem espaço útil para a indentação, a continuação começa na coluna 0.
+        let indent = if indent.chars().count() * 2 > width {
+            String::new()
+        } else {
+            indent
+        };
+        let mut current = String::new();
+        let mut current_len = 0;
+        for token in line_tokens(line) {
+            let mut token = token.as_str();
+            loop {
+                let len = token.chars().count();
+                if current_len + len <= width {
+                    current.push_str(token);
+                    current_len += len;
+                    break;
+                }
+                let at_line_start = current.trim().is_empty();
+                if !at_line_start {
+                    out.push_str(current.trim_end());
+                    out.push('\n');
+                    current = indent.clone();
+                    current_len = indent.chars().count();
+                    continue;
+                }
+                // O token sozinho não cabe: corta no limite da coluna.
+                let room = width.saturating_sub(current_len).max(1);
+                let split = token
+                    .char_indices()
+                    .nth(room)
+                    .map_or(token.len(), |(i, _)| i);
+                current.push_str(&token[..split]);
+                out.push_str(&current);
+                out.push('\n');
+                current = indent.clone();
+                current_len = indent.chars().count();
+                token = &token[split..];
+                if token.is_empty() {
+                    break;
+                }
+            }
+        }
+        out.push_str(&current);
+    }
+    out
+}
+
+/// Divide uma linha em tokens que podem ficar juntos numa quebra.
+fn line_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut buf = String::new();
+    let mut in_string = false;
+    let mut escape = false;
+
+    for c in line.chars() {
+        if in_string {
+            buf.push(c);
+            if escape {
+                escape = false;
+            } else if c == '\\' {
+                escape = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        if c == '"' && !buf.trim().is_empty() {
+            tokens.push(std::mem::take(&mut buf));
+        }
+        buf.push(c);
+        match c {
+            '"' => in_string = true,
+            ' ' | ',' | ':' => tokens.push(std::mem::take(&mut buf)),
+            _ => {}
+        }
+    }
+    if !buf.is_empty() {
+        tokens.push(buf);
+    }
+    tokens
+}
+
+fn json_to_spans<Link: Clone + 'static>(src: &str, th: Theme) -> Vec<Span<'static, Link>> {
     #[derive(Clone, Copy)]
     enum Kind {
         Default,
@@ -79,7 +311,7 @@ fn json_to_spans(src: &str, th: Theme) -> Vec<Span<'static>> {
         Punct,
     }
 
-    let mut out: Vec<Span<'static>> = Vec::new();
+    let mut out: Vec<Span<'static, Link>> = Vec::new();
     let mut buf = String::new();
 
     let chars: Vec<char> = src.chars().collect();
@@ -87,7 +319,7 @@ fn json_to_spans(src: &str, th: Theme) -> Vec<Span<'static>> {
     let mut in_string = false;
     let mut escape = false;
 
-    let flush = |k: Kind, b: &mut String, out: &mut Vec<Span<'static>>| {
+    let flush = |k: Kind, b: &mut String, out: &mut Vec<Span<'static, Link>>| {
         if b.is_empty() {
             return;
         }
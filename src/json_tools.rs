@@ -0,0 +1,393 @@
+use serde_json::Value;
+
+/// Summary of the shape of a JSON document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonStats {
+    pub keys: usize,
+    pub max_depth: usize,
+    pub nodes: usize,
+    pub top_level_arrays: Vec<(String, usize)>,
+}
+
+impl std::fmt::Display for JsonStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} keys · depth {} · {} nodes",
+            self.keys, self.max_depth, self.nodes
+        )?;
+        if !self.top_level_arrays.is_empty() {
+            let arrays: Vec<String> = self
+                .top_level_arrays
+                .iter()
+                .map(|(name, len)| format!("{name}: {len}"))
+                .collect();
+            write!(f, " · arrays: {}", arrays.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts keys and nodes, measures the nesting depth and reports the size of
+/// the arrays found at the top level of `value`.
+pub fn json_stats(value: &Value) -> JsonStats {
+    let mut stats = JsonStats::default();
+    walk(value, 1, &mut stats);
+    stats.top_level_arrays = match value {
+        Value::Array(items) => vec![("(root)".to_string(), items.len())],
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(key, v)| v.as_array().map(|items| (key.clone(), items.len())))
+            .collect(),
+        _ => Vec::new(),
+    };
+    stats
+}
+
+fn walk(value: &Value, depth: usize, stats: &mut JsonStats) {
+    stats.nodes += 1;
+    match value {
+        Value::Object(map) => {
+            stats.max_depth = stats.max_depth.max(depth);
+            stats.keys += map.len();
+            for v in map.values() {
+                walk(v, depth + 1, stats);
+            }
+        }
+        Value::Array(items) => {
+            stats.max_depth = stats.max_depth.max(depth);
+            for v in items {
+                walk(v, depth + 1, stats);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extracts the elements of a top-level JSON array from text that arrives
+/// in pieces, so each element can be shown as soon as it is complete.
+#[derive(Debug, Default)]
+pub struct JsonArrayStreamer {
+    buffer: String,
+    pos: usize,
+    depth: usize,
+    in_string: bool,
+    escape: bool,
+    started: bool,
+    finished: bool,
+    element_start: Option<usize>,
+}
+
+impl JsonArrayStreamer {
+    /// Feeds more text and returns the raw source of every element that was
+    /// completed by it. Fails when the data is not a top-level array.
+    pub fn push(&mut self, text: &str) -> Result<Vec<String>, String> {
+        self.buffer.push_str(text);
+        let mut elements = Vec::new();
+
+        let offset = self.pos;
+        for (i, c) in self.buffer[offset..].char_indices() {
+            let i = i + offset;
+            if self.finished {
+                if c.is_whitespace() {
+                    continue;
+                }
+                return Err("Unexpected data after the end of the array".to_string());
+            }
+            if !self.started {
+                match c {
+                    '[' => self.started = true,
+                    _ if c.is_whitespace() => {}
+                    _ => return Err("The response is not a JSON array".to_string()),
+                }
+                continue;
+            }
+            if self.in_string {
+                if self.escape {
+                    self.escape = false;
+                } else if c == '\\' {
+                    self.escape = true;
+                } else if c == '"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+            match c {
+                '"' => {
+                    self.in_string = true;
+                    self.element_start.get_or_insert(i);
+                }
+                '{' | '[' => {
+                    self.depth += 1;
+                    self.element_start.get_or_insert(i);
+                }
+                '}' | ']' if self.depth > 0 => self.depth -= 1,
+                // Commas inside a nested value don't end the element.
+                ',' if self.depth > 0 => {}
+                ',' | ']' => {
+                    if let Some(start) = self.element_start.take() {
+                        elements.push(self.buffer[start..i].trim().to_string());
+                    }
+                    self.finished = c == ']';
+                }
+                '}' => return Err("Unbalanced braces in the JSON array".to_string()),
+                _ if c.is_whitespace() => {}
+                _ => {
+                    self.element_start.get_or_insert(i);
+                }
+            }
+        }
+
+        // Drop everything that was already consumed.
+        match self.element_start {
+            Some(start) => {
+                self.buffer.drain(..start);
+                self.element_start = Some(0);
+            }
+            None => self.buffer.clear(),
+        }
+        self.pos = self.buffer.len();
+        Ok(elements)
+    }
+}
+
+/// What `prune_json` removes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneOpts {
+    pub hide_nulls: bool,
+    pub hide_empty: bool,
+}
+
+/// Recursively drops members and items of objects and arrays: nulls when
+/// `hide_nulls` is set, empty arrays and objects when `hide_empty` is set.
+/// Children are pruned first, so with `hide_empty` a container left empty
+/// by the pruning goes too. Empty strings, `false` and `0` are kept, and
+/// the top-level value itself is never removed.
+pub fn prune_json(value: &Value, opts: PruneOpts) -> Value {
+    let keep = |v: &Value| match v {
+        Value::Null => !opts.hide_nulls,
+        Value::Array(items) => !(opts.hide_empty && items.is_empty()),
+        Value::Object(map) => !(opts.hide_empty && map.is_empty()),
+        _ => true,
+    };
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), prune_json(v, opts)))
+                .filter(|(_, v)| keep(v))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| prune_json(v, opts))
+                .filter(|v| keep(v))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Anti-XSSI prefixes some servers put in front of JSON bodies.
+const XSSI_PREFIXES: [&str; 2] = [")]}',", ")]}'"];
+
+/// True when `body` starts like a JSON object/array and actually parses.
+pub fn looks_like_json(body: &str) -> bool {
+    let trimmed = body.trim_start();
+    (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<Value>(body).is_ok()
+}
+
+/// Parses `body` as JSON after stripping a BOM and anti-XSSI prefix.
+pub fn parse_json_lenient(body: &str) -> Option<Value> {
+    let mut body = body.trim_start_matches('\u{feff}').trim_start();
+    for prefix in XSSI_PREFIXES {
+        if let Some(rest) = body.strip_prefix(prefix) {
+            body = rest;
+            break;
+        }
+    }
+    serde_json::from_str(body).ok()
+}
+
+/// An array of objects laid out as rows and columns.
+#[derive(Debug, Clone, Default)]
+pub struct JsonTable {
+    /// Union of the keys of all rows, in order of first appearance.
+    pub columns: Vec<String>,
+    /// One cell per column; missing keys are empty.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Builds a table when `value` is a non-empty array whose elements are all
+/// objects. Nested objects and arrays show as `{…}` / `[…]`.
+pub fn json_table(value: &Value) -> Option<JsonTable> {
+    let items = value.as_array().filter(|items| !items.is_empty())?;
+    let objects: Vec<_> = items.iter().map(Value::as_object).collect::<Option<_>>()?;
+
+    let mut columns: Vec<String> = Vec::new();
+    for object in &objects {
+        for key in object.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    let rows = objects
+        .iter()
+        .map(|object| {
+            columns
+                .iter()
+                .map(|column| match object.get(column) {
+                    None => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Object(_)) => "{…}".to_string(),
+                    Some(Value::Array(_)) => "[…]".to_string(),
+                    Some(other) => other.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+    Some(JsonTable { columns, rows })
+}
+
+/// Resolves `path` inside `value`. A leading `/` means a JSON Pointer
+/// (RFC 6901, e.g. `/data/items/0/name`); anything else is the dotted form
+/// used by the tree view (`data.items[0].name`, `headers["x-id"]`).
+pub fn select_path<'a>(value: &'a Value, path: &str) -> Result<&'a Value, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Ok(value);
+    }
+    if path.starts_with('/') {
+        return value
+            .pointer(path)
+            .ok_or_else(|| format!("Nothing at pointer {path}"));
+    }
+
+    let mut current = value;
+    for segment in parse_dotted_path(path)? {
+        let next = match (&segment, current) {
+            (PathSegment::Key(key), Value::Object(map)) => map.get(key),
+            (PathSegment::Index(i), Value::Array(items)) => items.get(*i),
+            // `items.0` also indexes arrays.
+            (PathSegment::Key(key), Value::Array(items)) => {
+                key.parse::<usize>().ok().and_then(|i| items.get(i))
+            }
+            _ => None,
+        };
+        current = next.ok_or_else(|| format!("Nothing at {path}"))?;
+    }
+    Ok(current)
+}
+
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_dotted_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+    let mut chars = path.chars();
+    let mut key = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' | '[' => {
+                if !key.is_empty() {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+                if c == '[' {
+                    let mut inner = String::new();
+                    let mut in_string = false;
+                    loop {
+                        match chars.next() {
+                            Some('\\') if in_string => {
+                                inner.push('\\');
+                                inner.extend(chars.next());
+                            }
+                            Some('"') => {
+                                in_string = !in_string;
+                                inner.push('"');
+                            }
+                            Some(']') if !in_string => break,
+                            Some(c) => inner.push(c),
+                            None => return Err(format!("Unclosed [ in {path}")),
+                        }
+                    }
+                    let inner = inner.trim();
+                    segments.push(if inner.starts_with('"') {
+                        PathSegment::Key(
+                            serde_json::from_str(inner)
+                                .map_err(|_| format!("Invalid key {inner} in {path}"))?,
+                        )
+                    } else {
+                        PathSegment::Index(
+                            inner
+                                .parse()
+                                .map_err(|_| format!("Invalid index [{inner}] in {path}"))?,
+                        )
+                    });
+                }
+            }
+            _ => key.push(c),
+        }
+    }
+    if !key.is_empty() {
+        segments.push(PathSegment::Key(key));
+    }
+    Ok(segments)
+}
+
+/// Flattens `value` to sorted `(path, value)` pairs such as
+/// `("data.items.0.name", "\"foo\"")`. Values are JSON-encoded; empty
+/// objects and arrays are kept as `{}` / `[]`.
+pub fn flatten_json(value: &Value) -> Vec<(String, String)> {
+    fn walk(value: &Value, path: String, out: &mut Vec<(String, String)>) {
+        let join = |key: &str| {
+            if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{path}.{key}")
+            }
+        };
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    walk(child, join(key), out);
+                }
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for (i, child) in items.iter().enumerate() {
+                    walk(child, join(&i.to_string()), out);
+                }
+            }
+            leaf => out.push((path, leaf.to_string())),
+        }
+    }
+    let mut out = Vec::new();
+    walk(value, String::new(), &mut out);
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_array_of_objects_across_chunks() {
+        let src = r#"[{"a":1,"b":[2,3]}, {"c":"x,]}"} ,4]"#;
+        for split in 1..src.len() {
+            let mut streamer = JsonArrayStreamer::default();
+            let mut elements = streamer.push(&src[..split]).unwrap();
+            elements.extend(streamer.push(&src[split..]).unwrap());
+            assert_eq!(
+                elements,
+                [r#"{"a":1,"b":[2,3]}"#, r#"{"c":"x,]}"}"#, "4"],
+                "split at {split}"
+            );
+            assert!(streamer.finished);
+        }
+    }
+}
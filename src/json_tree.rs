@@ -0,0 +1,162 @@
+use iced::widget::{Column, button, column, container, row, text};
+use iced::{Color, Element, Font, padding};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::json_highlight::Theme;
+
+/// Renders `value` as a structured tree. Clicking a key or a value emits
+/// `on_copy` with the JSON path of that node (e.g. `data.items[2].id`);
+/// the arrow next to an object or array emits `on_toggle` with its path.
+/// Paths in `collapsed` are shown folded (the root is the empty path).
+pub fn view<'a, Message: Clone + 'a>(
+    value: &'a Value,
+    collapsed: &'a HashSet<String>,
+    on_copy: fn(String) -> Message,
+    on_toggle: fn(String) -> Message,
+) -> Element<'a, Message> {
+    let tree = Tree {
+        collapsed,
+        on_copy,
+        on_toggle,
+        theme: Theme::default(),
+    };
+    tree.node(value, String::new(), None)
+}
+
+/// Paths of every object and array in `value`, with their depth (the root
+/// is at depth 0).
+pub fn container_paths(value: &Value) -> Vec<(String, usize)> {
+    fn walk(value: &Value, path: String, depth: usize, out: &mut Vec<(String, usize)>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    walk(child, child_path(&path, key), depth + 1, out);
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    walk(child, format!("{path}[{i}]"), depth + 1, out);
+                }
+            }
+            _ => return,
+        }
+        out.push((path, depth));
+    }
+    let mut out = Vec::new();
+    walk(value, String::new(), 0, &mut out);
+    out
+}
+
+/// Appends `key` to `path`, using bracket notation when it is not a plain
+/// identifier.
+pub fn child_path(path: &str, key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    match (plain, path.is_empty()) {
+        (true, true) => key.to_string(),
+        (true, false) => format!("{path}.{key}"),
+        (false, _) => format!("{path}[{}]", serde_json::to_string(key).unwrap_or_default()),
+    }
+}
+
+struct Tree<'a, Message> {
+    collapsed: &'a HashSet<String>,
+    on_copy: fn(String) -> Message,
+    on_toggle: fn(String) -> Message,
+    theme: Theme,
+}
+
+impl<'a, Message: Clone + 'a> Tree<'a, Message> {
+    fn node(&self, value: &'a Value, path: String, label: Option<String>) -> Element<'a, Message> {
+        let theme = self.theme;
+        let label = label.map(|label| {
+            clickable(
+                text(format!("{label}:")).color(theme.key),
+                (self.on_copy)(path.clone()),
+            )
+        });
+
+        let (summary, is_empty) = match value {
+            Value::Object(map) => (format!("{{{} keys}}", map.len()), map.is_empty()),
+            Value::Array(items) => (format!("[{} items]", items.len()), items.is_empty()),
+            scalar => {
+                let leaf = clickable(scalar_text(scalar, theme), (self.on_copy)(path));
+                return match label {
+                    Some(label) => row![label, leaf].spacing(6).into(),
+                    None => leaf,
+                };
+            }
+        };
+        let folded = self.collapsed.contains(&path);
+        let arrow: Element<'a, Message> = if is_empty {
+            text(" ").font(Font::MONOSPACE).size(14).into()
+        } else {
+            clickable(
+                text(if folded { "▸" } else { "▾" }).color(theme.punct),
+                (self.on_toggle)(path.clone()),
+            )
+        };
+        let summary = text(summary).font(Font::MONOSPACE).color(theme.punct);
+        let header: Element<'a, Message> = match label {
+            Some(label) => row![arrow, label, summary].spacing(6).into(),
+            None => row![arrow, summary].spacing(6).into(),
+        };
+        if folded || is_empty {
+            return header;
+        }
+
+        let children: Vec<Element<'a, Message>> = match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, child)| {
+                    self.node(
+                        child,
+                        child_path(&path, key),
+                        Some(serde_json::to_string(key).unwrap_or_default()),
+                    )
+                })
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, child)| self.node(child, format!("{path}[{i}]"), Some(i.to_string())))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        column![
+            header,
+            container(Column::with_children(children).spacing(2)).padding(padding::left(20)),
+        ]
+        .spacing(2)
+        .into()
+    }
+}
+
+fn scalar_text<'a>(value: &Value, theme: Theme) -> iced::widget::Text<'a> {
+    let color: Color = match value {
+        Value::String(_) => theme.string,
+        Value::Number(_) => theme.number,
+        Value::Bool(_) => theme.boolean,
+        Value::Null => theme.null_,
+        _ => theme.default,
+    };
+    text(value.to_string()).color(color)
+}
+
+fn clickable<'a, Message: Clone + 'a>(
+    content: iced::widget::Text<'a>,
+    message: Message,
+) -> Element<'a, Message> {
+    button(content.font(Font::MONOSPACE).size(14))
+        .padding(0)
+        .style(button::text)
+        .on_press(message)
+        .into()
+}
@@ -0,0 +1,74 @@
+use serde_json::{Map, Value, json};
+
+/// A JSON-RPC 2.0 response: the request `id` and either its result or
+/// its error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcResponse {
+    Result {
+        id: Value,
+        result: Value,
+    },
+    Error {
+        id: Value,
+        code: i64,
+        message: String,
+        data: Option<Value>,
+    },
+}
+
+impl RpcResponse {
+    pub fn id(&self) -> &Value {
+        match self {
+            RpcResponse::Result { id, .. } | RpcResponse::Error { id, .. } => id,
+        }
+    }
+}
+
+/// Builds the request envelope for `method`. `params` is JSON text and
+/// must be an array or an object (JSON-RPC has no scalar params); when
+/// blank the member is left out.
+pub fn envelope(method: &str, params: &str, id: u64) -> Result<String, String> {
+    let method = method.trim();
+    if method.is_empty() {
+        return Err("Enter the method name".to_string());
+    }
+    let mut request = Map::new();
+    request.insert("jsonrpc".to_string(), json!("2.0"));
+    request.insert("method".to_string(), json!(method));
+    if !params.trim().is_empty() {
+        let params: Value =
+            serde_json::from_str(params).map_err(|e| format!("Params are not valid JSON: {e}"))?;
+        if !params.is_array() && !params.is_object() {
+            return Err("Params must be an array or an object".to_string());
+        }
+        request.insert("params".to_string(), params);
+    }
+    request.insert("id".to_string(), json!(id));
+    serde_json::to_string_pretty(&Value::Object(request)).map_err(|e| e.to_string())
+}
+
+/// Recognizes the `{jsonrpc: "2.0", id, result | error}` shape.
+pub fn detect(value: &Value) -> Option<RpcResponse> {
+    let object = value.as_object()?;
+    if object.get("jsonrpc")?.as_str()? != "2.0" {
+        return None;
+    }
+    let id = object.get("id")?.clone();
+    match (object.get("result"), object.get("error")) {
+        (Some(result), None) => Some(RpcResponse::Result {
+            id,
+            result: result.clone(),
+        }),
+        (None, Some(error)) => Some(RpcResponse::Error {
+            id,
+            code: error.get("code")?.as_i64()?,
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            data: error.get("data").cloned(),
+        }),
+        _ => None,
+    }
+}
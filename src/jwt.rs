@@ -0,0 +1,58 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Decodes the header and payload of a JWT. The signature is NOT verified.
+pub fn decode_jwt(token: &str) -> Result<(Value, Value), String> {
+    let token = token.trim();
+    let token = token
+        .strip_prefix("Bearer ")
+        .or_else(|| token.strip_prefix("bearer "))
+        .unwrap_or(token);
+    let mut segments = token.split('.');
+    let (Some(header), Some(payload), Some(_signature), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return Err("Not a JWT: expected three dot-separated segments".to_string());
+    };
+    Ok((
+        decode_segment(header, "header")?,
+        decode_segment(payload, "payload")?,
+    ))
+}
+
+fn decode_segment(segment: &str, what: &str) -> Result<Value, String> {
+    // Some issuers keep the padding even though the spec forbids it.
+    let bytes = URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|e| format!("Invalid base64url in the {what}: {e}"))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("The {what} is not JSON: {e}"))
+}
+
+/// Human-readable expiry from the `exp` claim, e.g. "expires in 12m".
+pub fn describe_exp(payload: &Value) -> Option<String> {
+    let exp = payload.get("exp")?.as_i64()?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let delta = exp - now;
+    Some(if delta >= 0 {
+        format!("expires in {}", human_duration(delta))
+    } else {
+        format!("expired {} ago", human_duration(-delta))
+    })
+}
+
+fn human_duration(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d {}h", s / 86_400, s % 86_400 / 3600),
+    }
+}
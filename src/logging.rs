@@ -0,0 +1,92 @@
+use crate::request::{Auth, HttpRequest, HttpResponse, is_sensitive_header};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_LOG_FILE: &str = "requests.log";
+
+/// How much of the response body ends up in the log.
+const BODY_SNIPPET_CHARS: usize = 1000;
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Serialize)]
+struct LogEntry<'a> {
+    timestamp_ms: u128,
+    method: String,
+    url: &'a str,
+    auth: String,
+    request_headers: BTreeMap<String, String>,
+    request_body: Option<&'a str>,
+    status: Option<u16>,
+    elapsed_ms: Option<u128>,
+    response_body: Option<String>,
+    error: Option<&'a str>,
+}
+
+/// Appends one NDJSON line describing `request` and its outcome to `path`.
+/// Credentials are replaced by `[redacted]` unless `include_auth` is set.
+pub fn log_exchange(
+    path: &Path,
+    request: &HttpRequest,
+    outcome: Result<&HttpResponse, &str>,
+    include_auth: bool,
+) -> Result<(), String> {
+    let request_headers = request
+        .headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive_header(name.as_str()) && !include_auth {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+
+    let auth = match request.auth {
+        Auth::None => String::new(),
+        Auth::Basic if include_auth => format!("Basic {}:{}", request.username, request.password),
+        Auth::Basic => format!("Basic {}:{REDACTED}", request.username),
+        Auth::Bearer if include_auth => format!("Bearer {}", request.token),
+        Auth::Bearer => format!("Bearer {REDACTED}"),
+    };
+
+    let (response, error) = match outcome {
+        Ok(response) => (Some(response), None),
+        Err(e) => (None, Some(e)),
+    };
+    let entry = LogEntry {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default(),
+        method: request
+            .method
+            .as_ref()
+            .map(|m| m.to_string())
+            .unwrap_or_default(),
+        url: &request.url,
+        auth,
+        request_headers,
+        request_body: request.body.as_deref(),
+        status: response.map(|r| r.status.as_u16()),
+        elapsed_ms: response.map(|r| r.elapsed.as_millis()),
+        response_body: response.map(|r| r.body.chars().take(BODY_SNIPPET_CHARS).collect()),
+        error,
+    };
+
+    let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Could not open log file {}: {e}", path.display()))?;
+    writeln!(file, "{line}").map_err(|e| format!("Could not write log file: {e}"))
+}
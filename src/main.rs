@@ -1,24 +1,95 @@
 #![cfg_attr(all(windows, not(debug_assertions)), windows_subsystem = "windows")]
 
+mod browser;
+mod cache;
+mod codegen;
+mod collection;
+mod config;
+mod diff;
+mod env;
+mod gist;
+mod hex;
+mod history;
 mod json_highlight;
+mod json_tools;
+mod json_tree;
+mod jsonrpc;
+mod jwt;
+mod logging;
+mod minimap;
+mod postman;
+mod prerequest;
+mod protobuf;
 mod request;
+mod schema;
+mod search;
+mod secrets;
+mod settings;
+mod share;
+mod storage;
+mod timeline;
+mod timestamps;
+mod undo;
 
-use crate::request::{Auth, HttpMethod, HttpRequest};
+use crate::cache::ConditionalCache;
+use crate::collection::{COLLECTION_FILE, RunSummary, SNIPPETS_FILE, SavedRequest, Snippet};
+use crate::config::AppConfig;
+use crate::diff::{
+    DiffLine, FieldChange, changed_fields, diff_ignoring, line_diff, normalize_json,
+    parse_ignore_paths,
+};
+use crate::env::Environments;
+use crate::history::{HISTORY_FILE, HistoryEntry};
+use crate::json_highlight::{EditorFont, Indent};
+use crate::json_tools::{
+    JsonArrayStreamer, JsonStats, JsonTable, PruneOpts, flatten_json, json_stats, json_table,
+    looks_like_json, parse_json_lenient, prune_json, select_path,
+};
+use crate::jsonrpc::RpcResponse;
+use crate::prerequest::{PreRequestStep, Transform};
+use crate::request::{
+    AcceptEncoding, Auth, BodyFormat, BodySource, ContentTypeMode, CookieInfo, FormEncoding,
+    FormField, FormFieldKind, HttpClient, HttpMethod, HttpRequest, HttpResponse, LineEnding,
+    SendError, StreamEvent, describe_status, encoded_url_preview, header_problem,
+    header_whitespace_hint, is_valid_method_token, join_wrapped_value, load_ca_cert, normalize_url,
+    override_authority, parse_header_block, parse_rate_limit, parse_raw_http, path_variable_names,
+    set_cookies, substitute_path_variables,
+};
+use crate::schema::{SchemaError, validate as validate_schema};
+use crate::search::{MAX_MATCHES, SearchMatch, find_matches};
+use crate::settings::{SETTINGS_FILE, Settings};
+use crate::undo::UndoStack;
 use iced::{
-    Length,
+    Color, Length,
     widget::{
-        Scrollable, button, column, horizontal_rule, pick_list, radio, row,
-        scrollable::{Direction, Scrollbar, Viewport},
-        text, text_editor,
-        text_editor::{Action, Content},
-        text_input,
+        PaneGrid, Scrollable, button, checkbox, column, container, horizontal_rule, pane_grid,
+        pick_list, radio, responsive, row,
+        scrollable::{self, RelativeOffset, Scrollbar, Viewport},
+        slider, text, text_editor,
+        text_editor::{Action, Content, Edit, Motion},
+        text_input, tooltip,
     },
 };
 
-use iced::Task;
+use iced::{Subscription, Task, keyboard};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Files up to this size are loaded into the body editor instead of being
+/// streamed from disk.
+const MAX_INLINE_BODY_FILE: u64 = 1024 * 1024;
+
+/// Above this size the body editor gets sluggish: live JSON validation is
+/// skipped and a warning suggests sending the body from a file.
+const LARGE_INLINE_BODY: usize = 1024 * 1024;
 
 fn main() -> iced::Result {
-    iced::application("PatchLite", App::update, App::view).run_with(App::new)
+    iced::application("PatchLite", App::update, App::view)
+        .subscription(App::subscription)
+        .exit_on_close_request(false)
+        .run_with(App::new)
 }
 
 #[derive(Default)]
@@ -33,36 +104,749 @@ struct App {
     request: HttpRequest,
     tab: Tab,
     request_body_content: text_editor::Content,
+    /// Block of `Key: Value` lines to add to the headers.
+    header_paste_content: text_editor::Content,
+    header_paste_replace: bool,
+    compact: bool,
+    side_by_side: bool,
+    panes: Panes,
+    host_override: String,
+    /// URL the user agreed to send credentials to over plain HTTP.
+    insecure_confirmed_url: Option<String>,
+    /// URL of a send waiting for that confirmation.
+    insecure_prompt: Option<String>,
+    custom_method: String,
+    raw_request_content: text_editor::Content,
+    response: Option<HttpResponse>,
+    response_stats: Option<JsonStats>,
+    response_json: Option<serde_json::Value>,
+    /// Set when the response is a JSON-RPC 2.0 result or error.
+    response_rpc: Option<RpcResponse>,
+    response_view: ResponseView,
+    search_query: String,
+    search_regex: bool,
+    search_matches: Vec<SearchMatch>,
+    /// Set instead of matches when the regex does not compile.
+    search_error: Option<String>,
+    /// Paths of the tree nodes that are folded.
+    tree_collapsed: HashSet<String>,
+    tree_depth: String,
+    response_table: Option<JsonTable>,
+    /// The response as sorted `path = value` lines for the Flat view.
+    response_flat: Option<String>,
+    /// Minimap line data of the response text, Flat and Hex views.
+    response_overview: minimap::Overview,
+    flat_overview: minimap::Overview,
+    hex_overview: minimap::Overview,
+    /// Last scroll position of the Flat/Hex view, drawn on the minimap.
+    response_viewport: Option<Viewport>,
+    /// Parse error of a body declared as JSON, with a caret at the position.
+    response_json_error: Option<String>,
+    path_filter: String,
+    path_filter_error: Option<String>,
+    prune: PruneOpts,
+    response_hex: String,
+    /// Length of the pretty-printed body, when the response is shown as JSON.
+    pretty_size: Option<usize>,
+    hex_include_request: bool,
+    force_json: bool,
+    /// Annotate timestamp-looking fields with the local time.
+    local_times: bool,
+    raw_body: bool,
+    /// Header editor shown above the response, for quick edit-and-resend.
+    quick_headers: bool,
+    json_hint: bool,
+    body_history: UndoStack,
+    decoded_jwt: Option<Result<(serde_json::Value, serde_json::Value), String>>,
+    sent_diff: Option<Result<Vec<DiffLine>, String>>,
+    /// Expected response, editable so volatile parts can be normalized.
+    baseline_content: text_editor::Content,
+    /// Paths left out of the baseline diff, e.g. `data.created_at, items.*.id`.
+    baseline_ignore: String,
+    baseline_diff: Option<Result<Vec<DiffLine>, String>>,
+    /// Body pinned with "Pin current"; every new response is diffed against it.
+    pinned_body: Option<String>,
+    pinned_diff: Option<Result<Vec<DiffLine>, String>>,
+    pinned_changes: Vec<FieldChange>,
+    schema_content: text_editor::Content,
+    gist_token: String,
+    gist_sharing: bool,
+    /// Description and markdown waiting for the user to confirm the upload.
+    gist_preview: Option<(String, String)>,
+    proto_pool: Option<prost_reflect::DescriptorPool>,
+    proto_descriptor: Option<PathBuf>,
+    proto_message: Option<String>,
+    /// Pretty-printed JSON of the decoded response, or why decoding failed.
+    proto_decoded: Option<Result<String, String>>,
+    schema_result: Option<Result<Vec<SchemaError>, String>>,
+    response_notice: String,
+    collection: Vec<SavedRequest>,
+    collection_name: String,
+    run_summary: Option<RunSummary>,
+    running_all: bool,
+    share_link: String,
+    collection_status: String,
+    snippets: Vec<Snippet>,
+    selected_snippet: Option<String>,
+    snippet_name: String,
+    poll_interval: String,
+    polling: bool,
+    in_flight: bool,
+    /// Bumped whenever an in-flight request is abandoned, so its late
+    /// result can be told apart from the current one.
+    request_generation: u64,
+    in_flight_handle: Option<iced::task::Handle>,
+    next_poll: Option<Instant>,
+    last_refreshed: Option<Instant>,
+    stream_json_arrays: bool,
+    /// Send `If-Modified-Since` for URLs that returned `Last-Modified`.
+    conditional_get: bool,
+    conditional_cache: ConditionalCache,
+    stream: Option<StreamState>,
+    /// Keep the streamed response scrolled to the bottom.
+    auto_scroll: bool,
+    /// Last vertical offset of the streamed response, to notice scrolling up.
+    stream_scroll_y: f32,
+    settings: Settings,
+    /// `{{name}}` variable sets; the active one is substituted at send time.
+    environments: Environments,
+    new_environment_name: String,
+    last_sent: Option<HttpRequest>,
+    /// Client shared by every send; see `http_client`.
+    http_client: Option<HttpClient>,
+    /// The last sent request before variables were resolved.
+    sent_template: Option<HttpRequest>,
+    history: Vec<HistoryEntry>,
+    /// History entries not written to disk yet.
+    history_dirty: bool,
+    /// Settings edited in a text field, written out by the next `Flush`.
+    settings_dirty: bool,
+    /// Same for environment variables.
+    environments_dirty: bool,
+    body_notice: String,
+    /// Name of the file the editor content was loaded from, if any.
+    body_file_name: Option<String>,
+    notes_content: text_editor::Content,
+    rpc_method: String,
+    rpc_params: text_editor::Content,
+    /// Id of the next JSON-RPC request built; increases with every build.
+    rpc_next_id: u64,
+    rpc_error: Option<String>,
+    settings_notice: String,
+    /// Text of number settings being edited, applied on submit.
+    number_drafts: HashMap<NumberSetting, String>,
+}
+
+/// Response being received chunk by chunk.
+struct StreamState {
+    status: reqwest::StatusCode,
+    headers: reqwest::header::HeaderMap,
+    redirects: Vec<String>,
+    body: Vec<u8>,
+    /// Trailing bytes of an incomplete UTF-8 sequence.
+    pending: Vec<u8>,
+    /// The body decoded so far, shown while it arrives.
+    text: String,
+    parser: JsonArrayStreamer,
+    elements: Vec<serde_json::Value>,
+    /// False once the body turned out not to be a JSON array.
+    parsing: bool,
+}
+
+impl StreamState {
+    fn new(
+        status: reqwest::StatusCode,
+        headers: reqwest::header::HeaderMap,
+        redirects: Vec<String>,
+    ) -> Self {
+        Self {
+            status,
+            headers,
+            redirects,
+            body: Vec::new(),
+            pending: Vec::new(),
+            text: String::new(),
+            parser: JsonArrayStreamer::default(),
+            elements: Vec::new(),
+            parsing: true,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.body.extend_from_slice(chunk);
+        self.pending.extend_from_slice(chunk);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) => e.valid_up_to(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..valid]).into_owned();
+        self.pending.drain(..valid);
+        self.text.push_str(&text);
+        if !self.parsing {
+            return;
+        }
+        match self.parser.push(&text) {
+            Ok(sources) => {
+                for source in sources {
+                    match serde_json::from_str(&source) {
+                        Ok(value) => self.elements.push(value),
+                        Err(_) => self.parsing = false,
+                    }
+                }
+            }
+            Err(_) => self.parsing = false,
+        }
+        if !self.parsing {
+            self.elements.clear();
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     Init,
     UpdateUrl(String),
+    UpdateHostOverride(String),
+    TogglePathTemplate(bool),
+    UpdatePathVariable(String, String),
     SendRequest,
+    ConfirmInsecureSend,
+    CancelInsecureSend,
+    UpdatePollInterval(String),
+    TogglePolling,
+    PollTick(Instant),
     UpdateMethod(HttpMethod),
+    UpdateCustomMethod(String),
     UpdateAuth(Auth),
     Scrolled(Viewport),
-    RequestCompleted(Result<String, String>),
+    /// Minimap click, as a fraction of the response height.
+    MinimapJump(f32),
+    RequestCompleted(u64, Result<HttpResponse, String>),
+    ToggleStreamJsonArrays(bool),
+    ToggleConditionalGet(bool),
+    ToggleAutoScroll(bool),
+    Stream(u64, StreamEvent),
     Clear,
     UpdateBody(text_editor::Action),
+    UpdateBodyFormat(BodyFormat),
+    UndoBody,
+    RedoBody,
+    EscapeBody,
+    UnescapeBody,
+    BrowseBodyFile,
+    BodyFileChosen(PathBuf),
+    BodyFileDropped(PathBuf),
+    ClearBodyFile,
+    JumpToBodyError(usize, usize),
+    UpdateContentTypeMode(ContentTypeMode),
+    ToggleAutoContentType(bool),
+    UpdateLineEnding(LineEnding),
+    UpdateCharset(String),
     UpdateTab(Tab),
+    ToggleCompact(bool),
+    ToggleSideBySide(bool),
+    PaneResized(pane_grid::ResizeEvent),
     UpdateUsername(String),
     UpdatePassword(String),
     UpdateToken(String),
+    DecodeJwt,
     UpdateHeaderKey(usize, String),
     UpdateHeaderValue(usize, String),
     RemoveHeaderRow(usize),
+    MoveHeaderRow(usize, Direction),
     AddHeaderRow,
+    JoinHeaderLines(usize),
+    PasteHeaderValue(usize, String),
+    TrimHeaderValue(usize),
+    UpdateHeaderPaste(text_editor::Action),
+    ToggleHeaderPasteReplace(bool),
+    ImportHeaderPaste,
+    ToggleFormBody(bool),
+    SetFormEncoding(FormEncoding),
+    AddFormRow,
+    RemoveFormRow(usize),
+    UpdateFormKey(usize, String),
+    UpdateFormValue(usize, String),
+    SetFormRowType(usize, FormFieldKind),
+    BrowseFormFile(usize),
     ResponseEditor(text_editor::Action),
+    CopyRustTypes,
+    CopyMinified,
+    CopyResponseHeaders,
+    OpenInBrowser,
+    SaveBaseline,
+    PinResponse,
+    Unpin,
+    UpdateBaseline(text_editor::Action),
+    UpdateNotes(text_editor::Action),
+    UpdateRpcMethod(String),
+    UpdateRpcParams(text_editor::Action),
+    /// Writes the JSON-RPC envelope into the body; `true` also sends it.
+    BuildRpcRequest(bool),
+    AddPreRequestStep,
+    RemovePreRequestStep(usize),
+    UpdatePreRequestVariable(usize, String),
+    UpdatePreRequestInput(usize, String),
+    UpdatePreRequestTransform(usize, Transform),
+    UpdatePreRequestKey(usize, String),
+    UpdateSchema(text_editor::Action),
+    BrowseProtoDescriptor,
+    ProtoDescriptorChosen(PathBuf),
+    SelectProtoType(String),
+    UpdateBaselineIgnore(String),
+    UpdateSearch(String),
+    ToggleSearchRegex(bool),
+    JumpToMatch(usize),
+    ToggleTreeNode(String),
+    ExpandAllTree,
+    CollapseAllTree,
+    UpdateTreeDepth(String),
+    ExpandTreeToDepth,
+    UpdateResponseView(ResponseView),
+    ToggleHexIncludeRequest(bool),
+    ToggleForceJson(bool),
+    ToggleLocalTimes(bool),
+    ToggleRawBody(bool),
+    ToggleQuickHeaders,
+    UpdatePathFilter(String),
+    ToggleHideNulls(bool),
+    ToggleHideEmpty(bool),
+    CopyPath(String),
+    UpdateRawRequest(text_editor::Action),
+    UpdateCollectionName(String),
+    SaveToCollection,
+    LoadSaved(usize),
+    ToggleStarred(usize),
+    RemoveSaved(usize),
+    SendAllParallel,
+    SendAllSequential,
+    SendAllCompleted(RunSummary),
+    UpdateShareLink(String),
+    ImportShareLink,
+    BrowseBatchImport,
+    BatchImportChosen(PathBuf),
+    ExportPostman,
+    PostmanExportChosen(PathBuf),
+    ImportPostman,
+    PostmanImportChosen(PathBuf),
+    CopyShareLink,
+    CopyAsHttpie,
+    CopySentAsCurl,
+    SelectSnippet(String),
+    InsertSnippet,
+    ReplaceWithSnippet,
+    DeleteSnippet,
+    UpdateSnippetName(String),
+    SaveSnippet,
+    ToggleKeychain(bool),
+    UpdateDefaultMethod(HttpMethod),
+    UpdateDefaultAuth(Auth),
+    UpdateIndent(Indent),
+    UpdateWrapColumn(String),
+    UpdateEditorFontSize(f32),
+    ToggleEditorMonospace(bool),
+    ZoomEditor(f32),
+    ToggleLogging(bool),
+    ToggleLogAuth(bool),
+    ToggleGistSharing(bool),
+    UpdateGistToken(String),
+    SaveGistToken,
+    ShareGist,
+    ConfirmGist,
+    CancelGist,
+    GistShared(Result<String, String>),
+    UpdateLogPath(String),
+    SaveLogPath,
+    LogPathChosen(String),
+    BrowseLogPath,
+    EditNumberSetting(NumberSetting, String),
+    SubmitNumberSetting(NumberSetting),
+    LoadHistory(usize),
+    Flush,
+    CloseRequested(iced::window::Id),
+    RemoveHistory(usize),
+    ClearHistory,
+    UpdateProxy(String),
+    ToggleSystemProxy(bool),
+    ToggleFollowRedirects(bool),
+    UpdateAcceptEncoding(AcceptEncoding),
+    BrowseCaCert,
+    AddCaCert(PathBuf),
+    RemoveCaCert(usize),
+    AddGlobalHeader,
+    SelectEnvironment(String),
+    UpdateNewEnvironmentName(String),
+    AddEnvironment,
+    RemoveEnvironment,
+    AddVariable,
+    ExportConfig,
+    ConfigExportChosen(PathBuf),
+    ImportConfig,
+    ConfigImportChosen(PathBuf),
+    UpdateVariableKey(usize, String),
+    UpdateVariableValue(usize, String),
+    RemoveVariable(usize),
+    InsertVariableInUrl(String),
+    InsertVariableInBody(String),
+    UpdateGlobalHeaderKey(usize, String),
+    UpdateGlobalHeaderValue(usize, String),
+    RemoveGlobalHeader(usize),
 }
 
-#[derive(Debug, Clone)]
+/// Colored line diff; `+` lines were added by the server, `-` lines dropped.
+fn diff_view(diff: &Result<Vec<DiffLine>, String>) -> iced::Element<'_, Message> {
+    let lines = match diff {
+        Ok(lines) => lines,
+        Err(e) => return text(e.clone()).into(),
+    };
+    let added = lines
+        .iter()
+        .filter(|l| matches!(l, DiffLine::Added(_)))
+        .count();
+    let removed = lines
+        .iter()
+        .filter(|l| matches!(l, DiffLine::Removed(_)))
+        .count();
+
+    let mut column = column![
+        text(format!(
+            "{added} lines added or changed by the server, {removed} dropped or changed"
+        ))
+        .size(12)
+    ];
+    for line in lines {
+        let (prefix, line, color) = match line {
+            DiffLine::Same(l) => ("  ", l, Color::from_rgb8(220, 220, 220)),
+            DiffLine::Added(l) => ("+ ", l, Color::from_rgb8(80, 250, 123)),
+            DiffLine::Removed(l) => ("- ", l, Color::from_rgb8(255, 100, 100)),
+        };
+        column = column.push(
+            text(format!("{prefix}{line}"))
+                .font(iced::Font::MONOSPACE)
+                .size(14)
+                .color(color),
+        );
+    }
+    Scrollable::new(column)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .on_scroll(Message::Scrolled)
+        .into()
+}
+
+/// Ctrl+= (or Ctrl++) and Ctrl+- to change the editor font size.
+fn zoom_shortcut(event: iced::Event) -> Option<Message> {
+    let iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else {
+        return None;
+    };
+    if !modifiers.command() {
+        return None;
+    }
+    match key.as_ref() {
+        keyboard::Key::Character("=" | "+") => Some(Message::ZoomEditor(1.0)),
+        keyboard::Key::Character("-") => Some(Message::ZoomEditor(-1.0)),
+        _ => None,
+    }
+}
+
+/// Ctrl+Z / Ctrl+Y (Ctrl+Shift+Z) for the body editor's undo history.
+fn body_shortcut(event: iced::Event) -> Option<Message> {
+    let iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else {
+        return None;
+    };
+    if !modifiers.command() {
+        return None;
+    }
+    match key.as_ref() {
+        keyboard::Key::Character("z") if modifiers.shift() => Some(Message::RedoBody),
+        keyboard::Key::Character("z") => Some(Message::UndoBody),
+        keyboard::Key::Character("y") => Some(Message::RedoBody),
+        _ => None,
+    }
+}
+
+/// Parses the wrap column typed in Settings; an empty field means 0 (off).
+fn parse_number_setting(input: &str) -> Option<u64> {
+    let input = input.trim();
+    if input.is_empty() {
+        Some(0)
+    } else {
+        input.parse().ok()
+    }
+}
+
+/// A number setting typed in a text field. The text is kept as a draft
+/// while it is edited and only applied (and saved) on submit, so clearing
+/// the field or typing "500" through "5" doesn't take effect on the way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum NumberSetting {
+    MaxHistory,
+    MaxRedirects,
+    MaxConcurrency,
+    PoolIdleTimeout,
+    PoolMaxIdle,
+    FastMs,
+    SlowMs,
+    RunnerDelayMs,
+}
+
+impl NumberSetting {
+    /// Smallest accepted value.
+    fn min(self) -> u64 {
+        match self {
+            NumberSetting::MaxHistory => 1,
+            NumberSetting::MaxRedirects => 1,
+            NumberSetting::MaxConcurrency => 1,
+            NumberSetting::PoolIdleTimeout => 0,
+            NumberSetting::PoolMaxIdle => 0,
+            NumberSetting::FastMs => 0,
+            NumberSetting::SlowMs => 0,
+            NumberSetting::RunnerDelayMs => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for NumberSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberSetting::MaxHistory => write!(f, "History size"),
+            NumberSetting::MaxRedirects => write!(f, "Redirect limit"),
+            NumberSetting::MaxConcurrency => write!(f, "Parallel requests"),
+            NumberSetting::PoolIdleTimeout => write!(f, "Idle connection timeout"),
+            NumberSetting::PoolMaxIdle => write!(f, "Idle connections per host"),
+            NumberSetting::FastMs => write!(f, "Fast response threshold"),
+            NumberSetting::SlowMs => write!(f, "Slow response threshold"),
+            NumberSetting::RunnerDelayMs => write!(f, "Runner delay"),
+        }
+    }
+}
+
+/// Rows shown by the table view; the rest is summarized.
+const MAX_TABLE_ROWS: usize = 1000;
+
+/// Field changes listed above the pinned diff; the rest is summarized.
+const MAX_PINNED_CHANGES: usize = 50;
+
+fn table_view(table: &JsonTable) -> iced::Element<'_, Message> {
+    let cell = |s: &str, color: Color| {
+        container(
+            text(s.to_string())
+                .font(iced::Font::MONOSPACE)
+                .size(12)
+                .color(color)
+                .wrapping(text::Wrapping::None),
+        )
+        .width(160)
+        .clip(true)
+    };
+    let header = table
+        .columns
+        .iter()
+        .fold(row![].spacing(10), |row, column| {
+            row.push(cell(column, Color::from_rgb8(67, 156, 255)))
+        });
+    let mut rows = column![header, horizontal_rule(5)].spacing(4);
+    for values in table.rows.iter().take(MAX_TABLE_ROWS) {
+        rows = rows.push(values.iter().fold(row![].spacing(10), |row, value| {
+            row.push(cell(value, Color::from_rgb8(220, 220, 220)))
+        }));
+    }
+    if table.rows.len() > MAX_TABLE_ROWS {
+        rows =
+            rows.push(text(format!("… {} more rows", table.rows.len() - MAX_TABLE_ROWS)).size(12));
+    }
+    Scrollable::new(rows)
+        .direction(scrollable::Direction::Both {
+            vertical: Scrollbar::default(),
+            horizontal: Scrollbar::default(),
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .on_scroll(Message::Scrolled)
+        .into()
+}
+
+/// Table of the cookies a response sets.
+fn cookies_view(cookies: Vec<CookieInfo>) -> iced::Element<'static, Message> {
+    let cell = |s: String, width: f32, color: Color| {
+        container(
+            text(s)
+                .font(iced::Font::MONOSPACE)
+                .size(12)
+                .color(color)
+                .wrapping(text::Wrapping::None),
+        )
+        .width(width)
+        .clip(true)
+    };
+    let heading = Color::from_rgb8(67, 156, 255);
+    let mut rows = column![
+        row![
+            cell("Name".to_string(), 140.0, heading),
+            cell("Value".to_string(), 200.0, heading),
+            cell("Domain".to_string(), 140.0, heading),
+            cell("Path".to_string(), 80.0, heading),
+            cell("Expires".to_string(), 200.0, heading),
+            cell("Flags".to_string(), 200.0, heading),
+        ]
+        .spacing(10)
+    ]
+    .spacing(2);
+    let plain = Color::from_rgb8(220, 220, 220);
+    for cookie in cookies {
+        let (expiry, flags) = (cookie.expiry(), cookie.flags());
+        rows = rows.push(
+            row![
+                cell(cookie.name, 140.0, plain),
+                cell(cookie.value, 200.0, plain),
+                cell(cookie.domain.unwrap_or_default(), 140.0, plain),
+                cell(cookie.path.unwrap_or_default(), 80.0, plain),
+                cell(expiry, 200.0, plain),
+                cell(flags, 200.0, plain),
+            ]
+            .spacing(10),
+        );
+    }
+    Scrollable::new(rows)
+        .direction(scrollable::Direction::Horizontal(Scrollbar::default()))
+        .width(Length::Fill)
+        .into()
+}
+
+/// Puts the cursor of `editor` at `column` (in characters) of logical line
+/// `line`. Up/Down move by visual line in a wrapped editor, so the logical
+/// line is checked after each step; page motions cover the distance first.
+fn move_cursor_to(editor: &mut Content, line: usize, column: usize) {
+    let line = line.min(editor.line_count().saturating_sub(1));
+    editor.perform(Action::Move(Motion::DocumentStart));
+    let step = |editor: &mut Content, motion: Motion| {
+        let before = editor.cursor_position();
+        editor.perform(Action::Move(motion));
+        editor.cursor_position() != before
+    };
+    while editor.cursor_position().0 < line {
+        if !step(editor, Motion::PageDown) && !step(editor, Motion::Down) {
+            break;
+        }
+    }
+    if line > 0 {
+        // Back onto the previous line, then down into the first visual row
+        // of the target.
+        while editor.cursor_position().0 >= line {
+            if !step(editor, Motion::Up) {
+                break;
+            }
+        }
+        step(editor, Motion::Down);
+    }
+    editor.perform(Action::Move(Motion::Home));
+    for _ in 0..column {
+        editor.perform(Action::Move(Motion::Right));
+    }
+}
+
+/// Scrollable of the Flat and Hex response views.
+fn response_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("response")
+}
+
+/// Scrollable holding the elements of a streamed response.
+fn stream_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("stream")
+}
+
+/// Below this width the side-by-side layout falls back to a single column.
+const MIN_SIDE_BY_SIDE_WIDTH: f32 = 900.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaneKind {
+    Request,
+    Response,
+}
+
+/// Request form on the left, response on the right.
+struct Panes(pane_grid::State<PaneKind>);
+
+impl Default for Panes {
+    fn default() -> Self {
+        Self(pane_grid::State::with_configuration(
+            pane_grid::Configuration::Split {
+                axis: pane_grid::Axis::Vertical,
+                ratio: 0.5,
+                a: Box::new(pane_grid::Configuration::Pane(PaneKind::Request)),
+                b: Box::new(pane_grid::Configuration::Pane(PaneKind::Response)),
+            },
+        ))
+    }
+}
+
+/// How the response body is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ResponseView {
+    #[default]
+    Text,
+    Tree,
+    DiffSent,
+    DiffBaseline,
+    DiffPinned,
+    Table,
+    Flat,
+    Schema,
+    Protobuf,
+    Hex,
+}
+
+impl ResponseView {
+    const ALL: [ResponseView; 10] = [
+        ResponseView::Text,
+        ResponseView::Tree,
+        ResponseView::DiffSent,
+        ResponseView::DiffBaseline,
+        ResponseView::DiffPinned,
+        ResponseView::Table,
+        ResponseView::Flat,
+        ResponseView::Schema,
+        ResponseView::Protobuf,
+        ResponseView::Hex,
+    ];
+}
+
+impl std::fmt::Display for ResponseView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResponseView::Text => write!(f, "Text"),
+            ResponseView::Tree => write!(f, "Tree"),
+            ResponseView::DiffSent => write!(f, "Diff sent vs received"),
+            ResponseView::DiffBaseline => write!(f, "Diff vs baseline"),
+            ResponseView::DiffPinned => write!(f, "Diff vs pinned"),
+            ResponseView::Table => write!(f, "Table"),
+            ResponseView::Flat => write!(f, "Flat"),
+            ResponseView::Schema => write!(f, "Schema check"),
+            ResponseView::Protobuf => write!(f, "Protobuf"),
+            ResponseView::Hex => write!(f, "Hex"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum Tab {
     None,
     Auth,
     Headers,
     Body,
+    Raw,
+    Collection,
+    Settings,
+    Form,
+    Docs,
+    History,
+    JsonRpc,
 }
 
 impl Default for Tab {
@@ -71,12 +855,33 @@ impl Default for Tab {
     }
 }
 impl Tab {
+    const ALL: [Tab; 11] = [
+        Tab::None,
+        Tab::Auth,
+        Tab::Headers,
+        Tab::Body,
+        Tab::Raw,
+        Tab::Collection,
+        Tab::Settings,
+        Tab::Form,
+        Tab::Docs,
+        Tab::History,
+        Tab::JsonRpc,
+    ];
+
     pub fn to_int(&self) -> Option<u8> {
         match self {
             Tab::None => Some(0),
             Tab::Auth => Some(1),
             Tab::Headers => Some(2),
             Tab::Body => Some(3),
+            Tab::Raw => Some(4),
+            Tab::Collection => Some(5),
+            Tab::Settings => Some(6),
+            Tab::Form => Some(7),
+            Tab::Docs => Some(8),
+            Tab::History => Some(9),
+            Tab::JsonRpc => Some(10),
         }
     }
     pub fn from_int(i: u8) -> Self {
@@ -85,192 +890,1889 @@ impl Tab {
             1 => Tab::Auth,
             2 => Tab::Headers,
             3 => Tab::Body,
+            4 => Tab::Raw,
+            5 => Tab::Collection,
+            6 => Tab::Settings,
+            7 => Tab::Form,
+            8 => Tab::Docs,
+            9 => Tab::History,
+            10 => Tab::JsonRpc,
             _ => Tab::None,
         }
     }
 }
 
-// #[derive(Default)]
-// struct HttpMethodComponent {
-//     method: HttpMethod,
-//     color: Color,
-// }
+impl std::fmt::Display for Tab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Tab::None => "Closed",
+            Tab::Auth => "Auth",
+            Tab::Headers => "Headers",
+            Tab::Body => "Body",
+            Tab::Raw => "Raw",
+            Tab::Collection => "Collection",
+            Tab::Settings => "Settings",
+            Tab::Form => "Form",
+            Tab::Docs => "Docs",
+            Tab::History => "History",
+            Tab::JsonRpc => "JSON-RPC",
+        };
+        write!(f, "{label}")
+    }
+}
 
 impl App {
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Init => {}
             Message::UpdateUrl(new_url) => {
+                self.stop_polling();
                 self.request.url = new_url;
             }
-            Message::SendRequest => {
-                if self.request.url.is_empty() {
-                    println!("URL is empty!");
+            Message::TogglePathTemplate(enabled) => {
+                self.request.path_template = enabled;
+            }
+            Message::UpdatePathVariable(name, value) => {
+                match self
+                    .request
+                    .path_vars
+                    .iter_mut()
+                    .find(|(key, _)| *key == name)
+                {
+                    Some(var) => var.1 = value,
+                    None => self.request.path_vars.push((name, value)),
                 }
+            }
+            Message::UpdateHostOverride(authority) => {
+                self.host_override = authority;
+            }
+            Message::SendRequest => {
+                let mut req = if matches!(self.tab, Tab::Raw) {
+                    match parse_raw_http(&self.raw_request_content.text()) {
+                        // Sent as written, without an automatic Content-Type.
+                        Ok(req) => HttpRequest {
+                            manual_content_type: true,
+                            ..req
+                        },
+                        Err(e) => {
+                            self.set_response_text(e);
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    if self.request.url.trim().is_empty() {
+                        self.set_response_text("Enter a URL".to_string());
+                        return Task::none();
+                    }
 
-                self.request.set_headers(&self.request_headers);
+                    let problems: Vec<String> = self
+                        .request_headers
+                        .iter()
+                        .filter_map(|(key, value)| header_problem(key, value))
+                        .collect();
+                    if !problems.is_empty() {
+                        self.set_response_text(format!(
+                            "Fix the headers before sending:\n{}",
+                            problems.join("\n")
+                        ));
+                        return Task::none();
+                    }
 
-                let req = self.request.clone();
-                return Task::perform(
-                    async move {
-                        let result = req.send().await;
-
-                        match result {
-                            Ok(response) => {
-                                let status = response.status();
-                                let body = response.text().await.unwrap_or_default();
-                                Ok(format!("Status: {}\nBody:\n{}", status, body))
-                            }
-                            Err(e) => Err(format!("Request failed: {}", e)),
-                        }
-                    },
-                    Message::RequestCompleted,
-                );
+                    let unfilled = self.request.unfilled_path_variables();
+                    if !unfilled.is_empty() {
+                        self.set_response_text(format!(
+                            "Fill in the path variables before sending: {}",
+                            unfilled.join(", ")
+                        ));
+                        return Task::none();
+                    }
+
+                    self.request.set_headers(&self.request_headers);
+                    self.request.clone()
+                };
+                let template = req.clone();
+                if let Err(e) = self.prepare_for_send(&mut req) {
+                    self.set_response_text(e);
+                    return Task::none();
+                }
+                if let Some(HttpMethod::Custom(method)) = &req.method
+                    && !is_valid_method_token(method)
+                {
+                    self.set_response_text(SendError::InvalidMethod(method.clone()).describe());
+                    return Task::none();
+                }
+                if req.sends_credentials_in_clear()
+                    && self.insecure_confirmed_url.as_deref() != Some(req.url.as_str())
+                {
+                    self.insecure_prompt = Some(req.url);
+                    self.stop_polling();
+                    return Task::none();
+                }
+                self.insecure_prompt = None;
+                if self.conditional_get {
+                    self.conditional_cache.prepare(&mut req);
+                }
+                let client = match self.http_client() {
+                    Ok(client) => client,
+                    Err(e) => {
+                        self.set_response_text(e.describe());
+                        self.stop_polling();
+                        return Task::none();
+                    }
+                };
+                // Only the newest send may complete.
+                self.cancel_in_flight();
+                self.in_flight = true;
+                self.last_sent = Some(req.clone());
+                self.sent_template = Some(template);
+                let generation = self.request_generation;
+                let task = if self.stream_json_arrays {
+                    self.stream = None;
+                    Task::run(req.send_streaming(client), move |event| {
+                        Message::Stream(generation, event)
+                    })
+                } else {
+                    Task::perform(
+                        async move { req.send(&client).await.map_err(|e| e.describe()) },
+                        move |result| Message::RequestCompleted(generation, result),
+                    )
+                };
+                let (task, handle) = task.abortable();
+                self.in_flight_handle = Some(handle);
+                return task;
+            }
+            Message::ConfirmInsecureSend => {
+                self.insecure_confirmed_url = self.insecure_prompt.take();
+                return self.update(Message::SendRequest);
+            }
+            Message::CancelInsecureSend => {
+                self.insecure_prompt = None;
+            }
+            Message::UpdatePollInterval(interval) => {
+                self.poll_interval = interval;
+            }
+            Message::TogglePolling => {
+                if self.polling {
+                    self.stop_polling();
+                } else if let Some(interval) = self.poll_interval_duration() {
+                    self.polling = true;
+                    self.next_poll = Some(Instant::now() + interval);
+                    return self.update(Message::SendRequest);
+                }
+            }
+            Message::PollTick(now) => {
+                let due = self.next_poll.is_some_and(|next| now >= next);
+                // Never overlap sends: wait for the in-flight request first.
+                if self.polling && due && !self.in_flight {
+                    if let Some(interval) = self.poll_interval_duration() {
+                        self.next_poll = Some(now + interval);
+                    }
+                    return self.update(Message::SendRequest);
+                }
+            }
+            Message::ToggleStreamJsonArrays(enabled) => {
+                self.stream_json_arrays = enabled;
+            }
+            Message::ToggleConditionalGet(enabled) => {
+                self.conditional_get = enabled;
+                if !enabled {
+                    self.conditional_cache.clear();
+                }
+            }
+            Message::ToggleAutoScroll(enabled) => {
+                self.auto_scroll = enabled;
+                if enabled && self.stream.is_some() {
+                    return scrollable::snap_to(stream_scroll_id(), RelativeOffset::END);
+                }
             }
-            Message::RequestCompleted(result) => match result {
-                Ok(response) => {
-                    self.response_message = response.clone().into();
-                    self.response_message_content =
-                        text_editor::Content::with_text(response.as_str());
+            Message::Stream(generation, _) if generation != self.request_generation => {}
+            Message::Stream(generation, event) => match event {
+                StreamEvent::Started {
+                    status,
+                    headers,
+                    redirects,
+                } => {
+                    self.stream = Some(StreamState::new(status, headers, redirects));
+                    self.stream_scroll_y = 0.0;
+                    self.set_response_text(format!("Status: {}\nBody:\n", status));
+                    self.response = None;
+                    self.response_stats = None;
+                    self.response_json = None;
+                    self.response_rpc = None;
+                    self.response_table = None;
+                    self.response_flat = None;
+                    self.flat_overview = minimap::Overview::default();
+                    self.response_json_error = None;
+                }
+                StreamEvent::Chunk(chunk) => {
+                    if let Some(stream) = &mut self.stream {
+                        stream.push(&chunk);
+                        if self.auto_scroll {
+                            return scrollable::snap_to(stream_scroll_id(), RelativeOffset::END);
+                        }
+                    }
+                }
+                StreamEvent::Finished(elapsed) => {
+                    if let Some(stream) = self.stream.take() {
+                        let response = HttpResponse {
+                            redirects: stream.redirects,
+                            ..HttpResponse::new(stream.status, stream.headers, stream.body, elapsed)
+                        };
+                        return self.update(Message::RequestCompleted(generation, Ok(response)));
+                    }
                 }
-                Err(e) => {
-                    self.response_message = e.clone().into();
-                    self.response_message_content = text_editor::Content::with_text(e.as_str());
+                StreamEvent::Failed(e) => {
+                    self.stream = None;
+                    return self.update(Message::RequestCompleted(generation, Err(e)));
                 }
             },
+            // Stale result of a request that was cancelled.
+            Message::RequestCompleted(generation, _) if generation != self.request_generation => {}
+            Message::RequestCompleted(_, result) => {
+                self.in_flight_handle = None;
+                self.record_history(result.as_ref().ok().map(|r| r.status.as_u16()));
+                let log_error = self.log_exchange(result.as_ref().map_err(String::as_str));
+                match result {
+                    Ok(response) => {
+                        self.in_flight = false;
+                        self.last_refreshed = Some(Instant::now());
+                        let (response, from_cache) = match &self.last_sent {
+                            Some(sent) if self.conditional_get => {
+                                self.conditional_cache.resolve(&sent.url, response)
+                            }
+                            _ => (response, false),
+                        };
+                        self.response_stats = serde_json::from_str(&response.body)
+                            .ok()
+                            .as_ref()
+                            .map(json_stats);
+                        self.sent_diff = self.diff_sent_body(&response.body);
+                        self.response = Some(response);
+                        self.refresh_baseline_diff();
+                        self.refresh_pinned_diff();
+                        self.refresh_schema_check();
+                        self.refresh_proto_decoded();
+                        self.refresh_response_display();
+                        self.response_notice = if from_cache {
+                            "304 Not Modified: showing the cached body.".to_string()
+                        } else {
+                            String::new()
+                        };
+                    }
+                    Err(e) => {
+                        self.in_flight = false;
+                        self.set_response_text(e);
+                        self.response = None;
+                        self.response_stats = None;
+                        self.response_json = None;
+                        self.response_rpc = None;
+                        self.response_table = None;
+                        self.response_flat = None;
+                        self.flat_overview = minimap::Overview::default();
+                        self.response_json_error = None;
+                        self.sent_diff = None;
+                        self.baseline_diff = None;
+                        self.pinned_diff = None;
+                        self.pinned_changes.clear();
+                        self.schema_result = None;
+                        self.proto_decoded = None;
+                    }
+                }
+                if let Some(e) = log_error {
+                    self.response_notice = e;
+                }
+            }
             Message::UpdateMethod(new_method) => {
+                self.stop_polling();
+                self.custom_method.clear();
                 self.request.method = Some(new_method);
             }
+            Message::UpdateCustomMethod(method) => {
+                let trimmed = method.trim();
+                self.request.method =
+                    (!trimmed.is_empty()).then(|| HttpMethod::Custom(trimmed.to_string()));
+                self.custom_method = method;
+            }
             Message::UpdateAuth(auth_type) => {
                 self.request.auth = auth_type;
             }
             Message::UpdateTab(tab) => {
                 self.tab = tab;
             }
+            Message::ToggleSideBySide(enabled) => {
+                self.side_by_side = enabled;
+            }
+            Message::PaneResized(pane_grid::ResizeEvent { split, ratio }) => {
+                self.panes.0.resize(split, ratio);
+            }
+            Message::ToggleCompact(compact) => {
+                self.compact = compact;
+            }
             Message::UpdateUsername(username) => {
                 self.request.username = username;
             }
             Message::UpdatePassword(password) => {
                 self.request.password = password;
             }
+            Message::DecodeJwt => {
+                self.decoded_jwt = Some(jwt::decode_jwt(&self.request.token));
+            }
             Message::UpdateToken(token) => {
                 self.request.token = token;
+                self.decoded_jwt = None;
             }
 
             Message::UpdateBody(action) => {
+                let before = action.is_edit().then(|| self.request_body_content.text());
+                if action.is_edit() {
+                    self.request.body_source = BodySource::Inline;
+                    self.body_notice.clear();
+                    self.body_file_name = None;
+                }
+                let force_step = matches!(
+                    action,
+                    Action::Edit(Edit::Paste(_) | Edit::Enter | Edit::Delete | Edit::Backspace)
+                );
                 self.request_body_content.perform(action);
-                self.request.body = self.request_body_content.text().to_string().into();
+                let after = self.request_body_content.text();
+                if let Some(before) = before.filter(|before| *before != after) {
+                    self.body_history.record(&before, force_step);
+                }
+                self.request.body = after.into();
+            }
+            Message::UndoBody => {
+                let current = self.request_body_content.text();
+                if let Some(text) = self.body_history.undo(&current) {
+                    self.restore_body_text(text);
+                }
+            }
+            Message::RedoBody => {
+                let current = self.request_body_content.text();
+                if let Some(text) = self.body_history.redo(&current) {
+                    self.restore_body_text(text);
+                }
+            }
+            Message::EscapeBody => {
+                let body = self.request_body_content.text();
+                if let Ok(escaped) = serde_json::to_string(body.trim_end_matches('\n')) {
+                    self.set_body_text(escaped);
+                }
+            }
+            Message::UnescapeBody => {
+                let body = self.request_body_content.text();
+                match serde_json::from_str::<String>(body.trim()) {
+                    Ok(raw) => self.set_body_text(raw),
+                    Err(e) => self.body_notice = format!("Not a JSON string literal: {e}"),
+                }
+            }
+            Message::BrowseBodyFile => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::BodyFileChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::BodyFileChosen(path) => {
+                // Small text files go into the editor; anything else is
+                // streamed from disk when the request is sent.
+                let inline = std::fs::metadata(&path)
+                    .is_ok_and(|meta| meta.len() <= MAX_INLINE_BODY_FILE)
+                    .then(|| std::fs::read_to_string(&path).ok())
+                    .flatten();
+                match inline {
+                    Some(body) => {
+                        self.set_body_text(body);
+                        self.body_file_name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned());
+                    }
+                    None => self.request.body_source = BodySource::File(path),
+                }
+            }
+            Message::BodyFileDropped(path) => {
+                self.tab = Tab::Body;
+                return self.update(Message::BodyFileChosen(path));
+            }
+            Message::ClearBodyFile => {
+                self.request.body_source = BodySource::Inline;
+                self.body_file_name = None;
+            }
+            Message::JumpToBodyError(line, column) => {
+                // serde_json counts lines from 1 and columns in bytes.
+                let body = self.request_body_content.text();
+                let line_text = body.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+                let column = json_highlight::char_column(line_text, column);
+                move_cursor_to(
+                    &mut self.request_body_content,
+                    line.saturating_sub(1),
+                    column,
+                );
+            }
+            Message::UpdateBodyFormat(format) => {
+                self.request.body_format = format;
+            }
+            Message::ToggleAutoContentType(enabled) => {
+                self.request.manual_content_type = !enabled;
+            }
+            Message::UpdateContentTypeMode(mode) => {
+                self.request.content_type = mode;
+            }
+            Message::UpdateLineEnding(line_ending) => {
+                self.request.line_ending = line_ending;
+            }
+            Message::UpdateCharset(charset) => {
+                self.request.charset = charset;
             }
             Message::ResponseEditor(action) => match &action {
                 Action::Edit(_) => {}
                 _ => self.response_message_content.perform(action),
             },
-            Message::UpdateHeaderKey(i, key) => {
-                if let Some(_header) = self.request_headers.get_mut(i) {
-                    self.request_headers[i].0 = key;
+            Message::CopyRustTypes => {
+                let Some(response) = &self.response else {
+                    return Task::none();
+                };
+                match serde_json::from_str::<serde_json::Value>(&response.body) {
+                    Ok(value) => {
+                        self.response_notice = "Rust types copied to clipboard.".to_string();
+                        return iced::clipboard::write(codegen::gen_rust_types(&value, "Root"));
+                    }
+                    Err(e) => self.response_notice = format!("Response is not JSON: {e}"),
                 }
             }
-            Message::UpdateHeaderValue(i, value) => {
-                if let Some(_header) = self.request_headers.get_mut(i) {
-                    self.request_headers[i].1 = value;
+            Message::CopyMinified => {
+                let Some(response) = &self.response else {
+                    return Task::none();
+                };
+                let copied = match parse_json_lenient(&response.body)
+                    .and_then(|value| serde_json::to_string(&value).ok())
+                {
+                    Some(minified) => {
+                        self.response_notice = "Minified JSON copied to clipboard.".to_string();
+                        minified
+                    }
+                    None => {
+                        self.response_notice =
+                            "Response is not JSON; copied it verbatim.".to_string();
+                        response.body.clone()
+                    }
+                };
+                return iced::clipboard::write(copied);
+            }
+            Message::CopyResponseHeaders => {
+                if let Some(response) = &self.response {
+                    self.response_notice = "Response headers copied to clipboard.".to_string();
+                    return iced::clipboard::write(response.header_block());
                 }
             }
-            Message::RemoveHeaderRow(i) => {
-                if i < self.request_headers.len() {
-                    self.request_headers.remove(i);
+            Message::OpenInBrowser => {
+                if let Some(response) = &self.response {
+                    self.response_notice = match browser::open_response(response) {
+                        Ok(path) => format!("Opened {}", path.display()),
+                        Err(e) => e,
+                    };
                 }
             }
-            Message::AddHeaderRow => {
-                self.request_headers.push((String::new(), String::new()));
+            Message::UpdateResponseView(view) => {
+                self.response_view = view;
+                self.response_viewport = None;
             }
-            Message::Scrolled(v) => {
-                self.response_message_offset =
-                    format!("{} {}", v.absolute_offset().x, v.absolute_offset().y)
+            Message::UpdateSearch(query) => {
+                self.search_query = query;
+                self.refresh_search();
             }
-            Message::Clear => {
-                self.response_message = None;
-                self.response_message_offset.clear();
-                self.method = None;
-                self.url.clear();
-                self.request_body = None;
-                self.request_headers.clear();
-                self.request = HttpRequest::default();
+            Message::ToggleSearchRegex(enabled) => {
+                self.search_regex = enabled;
+                self.refresh_search();
             }
-        }
-        Task::none()
-    }
-
-    fn view(&self) -> iced::Element<'_, Message> {
-        let method_pick_list = [
-            HttpMethod::GET,
-            HttpMethod::POST,
-            HttpMethod::PUT,
-            HttpMethod::PATCH,
-            HttpMethod::DELETE,
-        ];
-
-        let highlighted_response =
-            json_highlight::pretty_json_str(self.response_message.as_deref().unwrap_or(""));
-
-        //let response = column([text_editor(highlighted_response).into()]);
-
-        //todo add PaneGrid
-        let mut content = column![
-            row![
-                pick_list(method_pick_list, self.request.method, Message::UpdateMethod,)
-                    .placeholder("Select Method"),
-                text_input("", self.request.url.as_str()).on_input(|s| Message::UpdateUrl(s)),
-                button("Send").on_press(Message::SendRequest),
-            ]
-            .spacing(10)
-            .padding(10),
-            horizontal_rule(20),
-            row![
-                radio("Closed", 0, self.tab.to_int(), |i| {
-                    Message::UpdateTab(Tab::from_int(i))
-                }),
-                radio("Auth", 1, self.tab.to_int(), |i| {
-                    Message::UpdateTab(Tab::from_int(i))
-                }),
-                radio("Headers", 2, self.tab.to_int(), |i| {
-                    Message::UpdateTab(Tab::from_int(i))
-                }),
-                radio("Body", 3, self.tab.to_int(), |i| {
-                    Message::UpdateTab(Tab::from_int(i))
-                })
-            ]
-            .spacing(10)
-            .padding(10),
-            horizontal_rule(50),
-        ];
-
-        match self.tab {
-            Tab::None => {}
-            Tab::Auth => {
-                content = content.push(column![
-                    row![
-                        radio("No Auth", 0, self.request.auth.to_int(), |i| {
-                            Message::UpdateAuth(Auth::from_int(i))
-                        }),
-                        radio("Basic", 1, self.request.auth.to_int(), |i| {
-                            Message::UpdateAuth(Auth::from_int(i))
-                        }),
-                        radio("Bearer", 2, self.request.auth.to_int(), |i| {
-                            Message::UpdateAuth(Auth::from_int(i))
-                        }),
-                    ]
-                    .spacing(10)
-                    .padding(10),
-                    horizontal_rule(50),
-                ]);
-                match self.request.auth {
-                    Auth::Basic => {
-                        content = content.push(
-                            column![
-                                text("Basic Authentication selected."),
-                                text_input("Username", self.request.username.as_str())
-                                    .on_input(|s| Message::UpdateUsername(s)),
-                                text_input("Password", self.request.password.as_str())
+            Message::JumpToMatch(i) => {
+                let Some(found) = self.search_matches.get(i) else {
+                    return Task::none();
+                };
+                let (line, column, len) = (found.line, found.column(), found.char_len());
+                self.response_view = ResponseView::Text;
+                let editor = &mut self.response_message_content;
+                move_cursor_to(editor, line, column);
+                for _ in 0..len {
+                    editor.perform(Action::Select(Motion::Right));
+                }
+            }
+            Message::ToggleTreeNode(path) => {
+                if !self.tree_collapsed.remove(&path) {
+                    self.tree_collapsed.insert(path);
+                }
+            }
+            Message::ExpandAllTree => {
+                self.tree_collapsed.clear();
+            }
+            Message::CollapseAllTree => {
+                if let Some(json) = &self.response_json {
+                    self.tree_collapsed = json_tree::container_paths(json)
+                        .into_iter()
+                        .map(|(path, _)| path)
+                        .collect();
+                }
+            }
+            Message::UpdateTreeDepth(depth) => {
+                self.tree_depth = depth;
+            }
+            Message::ExpandTreeToDepth => {
+                if let (Some(json), Ok(depth)) =
+                    (&self.response_json, self.tree_depth.trim().parse::<usize>())
+                {
+                    self.tree_collapsed = json_tree::container_paths(json)
+                        .into_iter()
+                        .filter(|(_, level)| *level >= depth)
+                        .map(|(path, _)| path)
+                        .collect();
+                }
+            }
+            Message::SaveBaseline => {
+                if let Some(response) = &self.response {
+                    let baseline =
+                        normalize_json(&response.body).unwrap_or_else(|_| response.body.clone());
+                    self.baseline_content = text_editor::Content::with_text(&baseline);
+                    self.response_view = ResponseView::DiffBaseline;
+                    self.refresh_baseline_diff();
+                }
+            }
+            Message::PinResponse => {
+                if let Some(response) = &self.response {
+                    self.pinned_body = Some(response.body.clone());
+                    self.response_view = ResponseView::DiffPinned;
+                    self.refresh_pinned_diff();
+                }
+            }
+            Message::Unpin => {
+                self.pinned_body = None;
+                self.pinned_diff = None;
+                self.pinned_changes.clear();
+            }
+            Message::UpdateBaseline(action) => {
+                let edited = action.is_edit();
+                self.baseline_content.perform(action);
+                if edited {
+                    self.refresh_baseline_diff();
+                }
+            }
+            Message::UpdateRpcMethod(method) => {
+                self.rpc_method = method;
+                self.rpc_error = None;
+            }
+            Message::UpdateRpcParams(action) => {
+                self.rpc_params.perform(action);
+                self.rpc_error = None;
+            }
+            Message::BuildRpcRequest(send) => {
+                let id = self.rpc_next_id.max(1);
+                match jsonrpc::envelope(&self.rpc_method, &self.rpc_params.text(), id) {
+                    Ok(body) => {
+                        self.rpc_next_id = id + 1;
+                        self.rpc_error = None;
+                        let before = self.request_body_content.text();
+                        if before != body {
+                            self.body_history.record(&before, true);
+                        }
+                        self.restore_body_text(body);
+                        self.request.method = Some(HttpMethod::POST);
+                        self.custom_method.clear();
+                        if send {
+                            return self.update(Message::SendRequest);
+                        }
+                    }
+                    Err(e) => self.rpc_error = Some(e),
+                }
+            }
+            Message::UpdateNotes(action) => {
+                let edited = action.is_edit();
+                self.notes_content.perform(action);
+                if edited {
+                    self.request.notes = self.notes_content.text().trim_end().to_string();
+                }
+            }
+            Message::AddPreRequestStep => {
+                self.request.pre_request.push(PreRequestStep::default());
+            }
+            Message::RemovePreRequestStep(i) => {
+                if i < self.request.pre_request.len() {
+                    self.request.pre_request.remove(i);
+                }
+            }
+            Message::UpdatePreRequestVariable(i, variable) => {
+                if let Some(step) = self.request.pre_request.get_mut(i) {
+                    step.variable = variable;
+                }
+            }
+            Message::UpdatePreRequestInput(i, input) => {
+                if let Some(step) = self.request.pre_request.get_mut(i) {
+                    step.input = input;
+                }
+            }
+            Message::UpdatePreRequestTransform(i, transform) => {
+                if let Some(step) = self.request.pre_request.get_mut(i) {
+                    step.transform = transform;
+                }
+            }
+            Message::UpdatePreRequestKey(i, key) => {
+                if let Some(step) = self.request.pre_request.get_mut(i) {
+                    step.key = key;
+                }
+            }
+            Message::BrowseProtoDescriptor => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .add_filter("Descriptor set", &["pb", "desc", "protoset", "bin"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::ProtoDescriptorChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::ProtoDescriptorChosen(path) => {
+                match protobuf::load_descriptor(&path) {
+                    Ok(pool) => {
+                        let names = protobuf::message_names(&pool);
+                        if !self
+                            .proto_message
+                            .as_ref()
+                            .is_some_and(|m| names.contains(m))
+                        {
+                            self.proto_message = names.into_iter().next();
+                        }
+                        self.proto_pool = Some(pool);
+                        self.refresh_proto_decoded();
+                    }
+                    Err(e) => {
+                        self.proto_pool = None;
+                        self.proto_decoded = Some(Err(e));
+                    }
+                }
+                self.proto_descriptor = Some(path);
+            }
+            Message::SelectProtoType(name) => {
+                self.proto_message = Some(name);
+                self.refresh_proto_decoded();
+            }
+            Message::UpdateSchema(action) => {
+                let edited = action.is_edit();
+                self.schema_content.perform(action);
+                if edited {
+                    self.refresh_schema_check();
+                }
+            }
+            Message::UpdateBaselineIgnore(paths) => {
+                self.baseline_ignore = paths;
+                self.refresh_baseline_diff();
+            }
+            Message::ToggleHexIncludeRequest(enabled) => {
+                self.hex_include_request = enabled;
+                self.refresh_response_display();
+            }
+            Message::ToggleForceJson(enabled) => {
+                self.force_json = enabled;
+                self.refresh_response_display();
+            }
+            Message::ToggleLocalTimes(enabled) => {
+                self.local_times = enabled;
+                self.refresh_response_display();
+            }
+            Message::UpdatePathFilter(path) => {
+                self.path_filter = path;
+                self.refresh_response_display();
+            }
+            Message::ToggleQuickHeaders => {
+                self.quick_headers = !self.quick_headers;
+            }
+            Message::ToggleRawBody(enabled) => {
+                self.raw_body = enabled;
+                self.refresh_response_display();
+            }
+            Message::ToggleHideNulls(enabled) => {
+                self.prune.hide_nulls = enabled;
+                self.refresh_response_display();
+            }
+            Message::ToggleHideEmpty(enabled) => {
+                self.prune.hide_empty = enabled;
+                self.refresh_response_display();
+            }
+            Message::CopyPath(path) => {
+                self.response_notice = format!("Copied path {path}");
+                return iced::clipboard::write(path);
+            }
+            Message::UpdateRawRequest(action) => {
+                self.raw_request_content.perform(action);
+            }
+            Message::UpdateCollectionName(name) => {
+                self.collection_name = name;
+            }
+            Message::SaveToCollection => {
+                // Saved as typed: `prepare_for_send` adds the automatic
+                // Content-Type again on every send, runners included.
+                self.request.set_headers(&self.request_headers);
+                self.collection.push(SavedRequest::new(
+                    std::mem::take(&mut self.collection_name),
+                    self.request.clone(),
+                ));
+                self.save_collection();
+            }
+            Message::LoadSaved(i) => {
+                if let Some(saved) = self.collection.get(i) {
+                    self.load_request(saved.request.clone());
+                }
+            }
+            Message::LoadHistory(i) => {
+                if let Some(entry) = self.history.get(i) {
+                    self.load_request(entry.request.clone());
+                }
+            }
+            Message::RemoveHistory(i) => {
+                if i < self.history.len() {
+                    self.history.remove(i);
+                    self.save_history();
+                }
+            }
+            Message::ClearHistory => {
+                self.history.clear();
+                self.save_history();
+            }
+            Message::ToggleStarred(i) => {
+                if let Some(saved) = self.collection.get_mut(i) {
+                    saved.starred = !saved.starred;
+                    self.save_collection();
+                }
+            }
+            Message::RemoveSaved(i) => {
+                if i < self.collection.len() {
+                    let removed = self.collection.remove(i);
+                    if self.settings.use_keychain {
+                        secrets::remove(&removed.id);
+                    }
+                    self.save_collection();
+                }
+            }
+            Message::SendAllParallel => {
+                if self.running_all || self.collection.is_empty() {
+                    return Task::none();
+                }
+                let requests = match self.runner_requests() {
+                    Ok(requests) => requests,
+                    Err(e) => {
+                        self.collection_status = e;
+                        return Task::none();
+                    }
+                };
+                let client = match self.http_client() {
+                    Ok(client) => client,
+                    Err(e) => {
+                        self.collection_status = e.describe();
+                        return Task::none();
+                    }
+                };
+                self.running_all = true;
+                return Task::perform(
+                    collection::send_all(requests, client, self.settings.max_concurrency),
+                    Message::SendAllCompleted,
+                );
+            }
+            Message::SendAllSequential => {
+                if self.running_all || self.collection.is_empty() {
+                    return Task::none();
+                }
+                let requests = match self.runner_requests() {
+                    Ok(requests) => requests,
+                    Err(e) => {
+                        self.collection_status = e;
+                        return Task::none();
+                    }
+                };
+                let client = match self.http_client() {
+                    Ok(client) => client,
+                    Err(e) => {
+                        self.collection_status = e.describe();
+                        return Task::none();
+                    }
+                };
+                self.running_all = true;
+                return Task::perform(
+                    collection::send_sequential(
+                        requests,
+                        client,
+                        Duration::from_millis(self.settings.runner_delay_ms),
+                    ),
+                    Message::SendAllCompleted,
+                );
+            }
+            Message::SendAllCompleted(summary) => {
+                self.running_all = false;
+                self.run_summary = Some(summary);
+            }
+            Message::UpdateShareLink(link) => {
+                self.share_link = link;
+            }
+            Message::ImportShareLink => match share::decode_request(&self.share_link) {
+                Ok(request) => {
+                    self.load_request(request);
+                    self.share_link.clear();
+                    self.collection_status = "Request imported.".to_string();
+                }
+                Err(e) => self.collection_status = e,
+            },
+            Message::BrowseBatchImport => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::BatchImportChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::ExportPostman => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_file_name(postman::POSTMAN_EXPORT_FILE)
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::PostmanExportChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::PostmanExportChosen(path) => {
+                let exported = postman::export_postman("PatchLite", &self.collection);
+                self.collection_status = match std::fs::write(&path, exported) {
+                    Ok(()) => format!(
+                        "Exported {} requests to {}",
+                        self.collection.len(),
+                        path.display()
+                    ),
+                    Err(e) => format!("Could not write {}: {e}", path.display()),
+                };
+            }
+            Message::ImportPostman => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .add_filter("Postman collection", &["json"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::PostmanImportChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::PostmanImportChosen(path) => {
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {e}", path.display()))
+                    .and_then(|text| postman::from_postman(&text));
+                match parsed {
+                    Ok(import) => {
+                        let count = import.requests.len();
+                        self.collection.extend(import.requests);
+                        self.save_collection();
+                        self.collection_status = format!("Imported {count} requests from Postman.");
+                        if !import.variables.is_empty() {
+                            // Collection variables become an environment of
+                            // the same name; existing values are overwritten.
+                            self.environments.add(import.name.clone());
+                            if let Some(vars) = self.environments.active_vars_mut() {
+                                for (key, value) in import.variables {
+                                    match vars.iter_mut().find(|(k, _)| *k == key) {
+                                        Some(existing) => existing.1 = value,
+                                        None => vars.push((key, value)),
+                                    }
+                                }
+                            }
+                            self.save_environments();
+                            self.collection_status.push_str(&format!(
+                                " Variables saved to environment '{}'.",
+                                import.name
+                            ));
+                        }
+                    }
+                    Err(e) => self.collection_status = e,
+                }
+            }
+            Message::BatchImportChosen(path) => {
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {e}", path.display()))
+                    .and_then(|text| collection::import_batch(&text));
+                match parsed {
+                    Ok((imported, failures)) => {
+                        let count = imported.len();
+                        self.collection.extend(imported);
+                        if count > 0 {
+                            self.save_collection();
+                        }
+                        self.collection_status = if failures.is_empty() {
+                            format!("Imported {count} requests.")
+                        } else {
+                            format!(
+                                "Imported {count} requests, {} failed:\n{}",
+                                failures.len(),
+                                failures.join("\n")
+                            )
+                        };
+                    }
+                    Err(e) => self.collection_status = e,
+                }
+            }
+            Message::CopyAsHttpie => {
+                self.request.set_headers(&self.request_headers);
+                self.request.apply_content_type();
+                let mut request = self.request.clone();
+                request.merge_global_headers(&self.settings.global_headers);
+                return iced::clipboard::write(request.to_httpie());
+            }
+            Message::CopySentAsCurl => {
+                // The snapshot taken at send time, with variables, global
+                // headers and the automatic Content-Type already applied.
+                if let Some(sent) = &self.last_sent {
+                    return iced::clipboard::write(sent.to_curl());
+                }
+            }
+            Message::CopyShareLink => {
+                self.request.set_headers(&self.request_headers);
+                self.request.apply_content_type();
+                self.collection_status =
+                    "Share link copied to clipboard (credentials left out).".to_string();
+                return iced::clipboard::write(share::encode_request(&self.request));
+            }
+            Message::ToggleKeychain(enabled) => {
+                self.settings.use_keychain = enabled;
+                if !enabled {
+                    for saved in &self.collection {
+                        secrets::remove(&saved.id);
+                    }
+                }
+                self.collection_status.clear();
+                self.save_settings();
+                self.save_collection();
+            }
+            Message::UpdateDefaultMethod(method) => {
+                self.settings.default_method = method;
+                self.save_settings();
+            }
+            Message::UpdateDefaultAuth(auth) => {
+                self.settings.default_auth = auth;
+                self.save_settings();
+            }
+            Message::UpdateIndent(indent) => {
+                self.settings.indent = indent;
+                self.save_settings();
+                self.refresh_response_display();
+            }
+            Message::UpdateWrapColumn(column) => {
+                if let Some(column) = parse_number_setting(&column) {
+                    self.settings.wrap_column = column as usize;
+                    self.settings_dirty = true;
+                    self.refresh_response_display();
+                }
+            }
+            Message::UpdateEditorFontSize(size) => {
+                self.settings.editor_font.size = size.round();
+                self.save_settings();
+            }
+            Message::ToggleEditorMonospace(enabled) => {
+                self.settings.editor_font.monospace = enabled;
+                self.save_settings();
+            }
+            Message::ZoomEditor(delta) => {
+                self.settings.editor_font = self.settings.editor_font.zoomed(delta);
+                self.save_settings();
+            }
+            Message::ToggleLogging(enabled) => {
+                self.settings.log_enabled = enabled;
+                if enabled
+                    && self.settings.log_path.is_empty()
+                    && let Some(dir) = storage::data_dir()
+                {
+                    self.settings.log_path =
+                        dir.join(logging::DEFAULT_LOG_FILE).display().to_string();
+                }
+                self.save_settings();
+            }
+            Message::ToggleGistSharing(enabled) => {
+                self.settings.gist_enabled = enabled;
+                self.save_settings();
+                if enabled && self.gist_token.is_empty() {
+                    match secrets::fetch_value(gist::TOKEN_ENTRY) {
+                        Ok(token) => self.gist_token = token,
+                        Err(e) => self.settings_notice = e,
+                    }
+                }
+            }
+            Message::UpdateGistToken(token) => {
+                self.gist_token = token;
+            }
+            Message::SaveGistToken => {
+                self.settings_notice =
+                    match secrets::store_value(gist::TOKEN_ENTRY, self.gist_token.trim()) {
+                        Ok(()) => String::new(),
+                        Err(e) => e,
+                    };
+            }
+            Message::ShareGist => {
+                if let (Some(sent), Some(response)) = (&self.last_sent, &self.response) {
+                    let content = gist::exchange_markdown(sent, response);
+                    let description = format!(
+                        "{} {}",
+                        sent.method
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_default(),
+                        gist::redact_url(&sent.url)
+                    );
+                    self.gist_preview = Some((description, content));
+                }
+            }
+            Message::Flush => self.flush(),
+            Message::CloseRequested(id) => {
+                self.flush();
+                return iced::window::close(id);
+            }
+            Message::ConfirmGist => {
+                if let Some((description, content)) = self.gist_preview.take() {
+                    let client = match self.http_client() {
+                        Ok(client) => client,
+                        Err(e) => {
+                            self.response_notice = e.describe();
+                            return Task::none();
+                        }
+                    };
+                    self.gist_sharing = true;
+                    self.response_notice = "Uploading to Gist...".to_string();
+                    return Task::perform(
+                        gist::upload(
+                            self.gist_token.trim().to_string(),
+                            description,
+                            content,
+                            client,
+                        ),
+                        Message::GistShared,
+                    );
+                }
+            }
+            Message::CancelGist => {
+                self.gist_preview = None;
+            }
+            Message::GistShared(result) => {
+                self.gist_sharing = false;
+                match result {
+                    Ok(url) => {
+                        self.response_notice = format!("Gist created: {url} (copied)");
+                        return iced::clipboard::write(url);
+                    }
+                    Err(e) => self.response_notice = e,
+                }
+            }
+            Message::ToggleLogAuth(enabled) => {
+                self.settings.log_auth = enabled;
+                self.save_settings();
+            }
+            Message::UpdateLogPath(path) => {
+                self.settings.log_path = path;
+            }
+            Message::SaveLogPath => {
+                self.save_settings();
+            }
+            Message::LogPathChosen(path) => {
+                self.settings.log_path = path;
+                self.save_settings();
+            }
+            Message::BrowseLogPath => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_file_name(logging::DEFAULT_LOG_FILE)
+                            .save_file()
+                            .await
+                            .map(|file| file.path().display().to_string())
+                    },
+                    |path| match path {
+                        Some(path) => Message::LogPathChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::EditNumberSetting(setting, text) => {
+                self.number_drafts.insert(setting, text);
+            }
+            Message::SubmitNumberSetting(setting) => {
+                let Some(draft) = self.number_drafts.get(&setting) else {
+                    return Task::none();
+                };
+                match draft.trim().parse::<u64>() {
+                    Ok(value) if value >= setting.min() => {
+                        self.number_drafts.remove(&setting);
+                        self.settings_notice.clear();
+                        self.set_number_setting(setting, value);
+                    }
+                    _ => {
+                        self.settings_notice = format!(
+                            "{setting} must be a whole number of at least {}",
+                            setting.min()
+                        );
+                    }
+                }
+            }
+            Message::UpdateAcceptEncoding(encoding) => {
+                self.settings.client.accept_encoding = encoding;
+                self.save_settings();
+            }
+            Message::UpdateProxy(proxy) => {
+                self.settings.client.proxy = proxy;
+                self.settings_dirty = true;
+            }
+            Message::ToggleFollowRedirects(enabled) => {
+                self.settings.client.follow_redirects = enabled;
+                self.save_settings();
+            }
+            Message::ToggleSystemProxy(enabled) => {
+                self.settings.client.use_system_proxy = enabled;
+                self.save_settings();
+            }
+            Message::BrowseCaCert => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .add_filter("PEM certificate", &["pem", "crt", "cer"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::AddCaCert(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::AddCaCert(path) => match load_ca_cert(&path) {
+                Ok(_) => {
+                    self.settings_notice.clear();
+                    if !self.settings.client.ca_certs.contains(&path) {
+                        self.settings.client.ca_certs.push(path);
+                        self.save_settings();
+                    }
+                }
+                Err(e) => self.settings_notice = e.describe(),
+            },
+            Message::RemoveCaCert(i) => {
+                if i < self.settings.client.ca_certs.len() {
+                    self.settings.client.ca_certs.remove(i);
+                    self.save_settings();
+                }
+            }
+            Message::AddGlobalHeader => {
+                self.settings
+                    .global_headers
+                    .push((String::new(), String::new()));
+                self.save_settings();
+            }
+            Message::UpdateGlobalHeaderKey(i, key) => {
+                if let Some(header) = self.settings.global_headers.get_mut(i) {
+                    header.0 = key;
+                    self.settings_dirty = true;
+                }
+            }
+            Message::UpdateGlobalHeaderValue(i, value) => {
+                if let Some(header) = self.settings.global_headers.get_mut(i) {
+                    header.1 = value;
+                    self.settings_dirty = true;
+                }
+            }
+            Message::RemoveGlobalHeader(i) => {
+                if i < self.settings.global_headers.len() {
+                    self.settings.global_headers.remove(i);
+                    self.save_settings();
+                }
+            }
+            Message::SelectEnvironment(name) => {
+                self.environments.active = Some(name);
+                self.save_environments();
+            }
+            Message::UpdateNewEnvironmentName(name) => {
+                self.new_environment_name = name;
+            }
+            Message::AddEnvironment => {
+                let name = self.new_environment_name.trim().to_string();
+                if !name.is_empty() {
+                    self.environments.add(name);
+                    self.new_environment_name.clear();
+                    self.save_environments();
+                }
+            }
+            Message::RemoveEnvironment => {
+                self.environments.remove_active();
+                self.save_environments();
+            }
+            Message::ExportConfig => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .set_file_name(config::CONFIG_EXPORT_FILE)
+                            .save_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::ConfigExportChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::ConfigExportChosen(path) => {
+                let exported = AppConfig {
+                    settings: self.settings.clone(),
+                    environments: self.environments.clone(),
+                };
+                if let Err(e) = config::export(&path, &exported) {
+                    self.settings_notice = e;
+                }
+            }
+            Message::ImportConfig => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    |path| match path {
+                        Some(path) => Message::ConfigImportChosen(path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::ConfigImportChosen(path) => match config::import(&path) {
+                Ok(imported) => {
+                    self.settings = imported.settings;
+                    self.environments = imported.environments;
+                    self.settings_notice.clear();
+                    self.number_drafts.clear();
+                    self.save_settings();
+                    self.save_environments();
+                }
+                Err(e) => self.settings_notice = e,
+            },
+            Message::AddVariable => {
+                if self.environments.active_vars_mut().is_none() {
+                    self.environments.add("Default".to_string());
+                }
+                if let Some(vars) = self.environments.active_vars_mut() {
+                    vars.push((String::new(), String::new()));
+                }
+                self.save_environments();
+            }
+            Message::UpdateVariableKey(i, key) => {
+                if let Some(variable) = self
+                    .environments
+                    .active_vars_mut()
+                    .and_then(|vars| vars.get_mut(i))
+                {
+                    variable.0 = key;
+                    self.environments_dirty = true;
+                }
+            }
+            Message::UpdateVariableValue(i, value) => {
+                if let Some(variable) = self
+                    .environments
+                    .active_vars_mut()
+                    .and_then(|vars| vars.get_mut(i))
+                {
+                    variable.1 = value;
+                    self.environments_dirty = true;
+                }
+            }
+            Message::RemoveVariable(i) => {
+                if let Some(vars) = self.environments.active_vars_mut()
+                    && i < vars.len()
+                {
+                    vars.remove(i);
+                    self.save_environments();
+                }
+            }
+            Message::InsertVariableInUrl(name) => {
+                // text_input does not expose its cursor, so the token is appended.
+                self.stop_polling();
+                self.request.url.push_str(&env::token(&name));
+            }
+            Message::InsertVariableInBody(name) => {
+                let before = self.request_body_content.text();
+                self.body_history.record(&before, true);
+                self.request_body_content
+                    .perform(Action::Edit(Edit::Paste(Arc::new(env::token(&name)))));
+                self.request.body = self.request_body_content.text().into();
+                self.request.body_source = BodySource::Inline;
+                self.body_notice.clear();
+            }
+            Message::SelectSnippet(name) => {
+                self.selected_snippet = Some(name);
+            }
+            Message::InsertSnippet => {
+                if let Some(snippet) = self.selected_snippet() {
+                    let text = snippet.text.clone();
+                    let before = self.request_body_content.text();
+                    self.body_history.record(&before, true);
+                    self.request_body_content
+                        .perform(Action::Edit(Edit::Paste(Arc::new(text))));
+                    self.request.body = self.request_body_content.text().into();
+                }
+            }
+            Message::ReplaceWithSnippet => {
+                if let Some(snippet) = self.selected_snippet() {
+                    let text = snippet.text.clone();
+                    self.set_body_text(text);
+                }
+            }
+            Message::DeleteSnippet => {
+                if let Some(name) = self.selected_snippet.take() {
+                    self.snippets.retain(|s| s.name != name);
+                    self.save_snippets();
+                }
+            }
+            Message::UpdateSnippetName(name) => {
+                self.snippet_name = name;
+            }
+            Message::SaveSnippet => {
+                let name = self.snippet_name.trim().to_string();
+                if !name.is_empty() {
+                    let text = self.request.body.clone().unwrap_or_default();
+                    match self.snippets.iter_mut().find(|s| s.name == name) {
+                        Some(existing) => existing.text = text,
+                        None => self.snippets.push(Snippet {
+                            name: name.clone(),
+                            text,
+                        }),
+                    }
+                    self.snippet_name.clear();
+                    self.selected_snippet = Some(name);
+                    self.save_snippets();
+                }
+            }
+            Message::UpdateHeaderKey(i, key) => {
+                // Header names never contain whitespace.
+                if let Some(_header) = self.request_headers.get_mut(i) {
+                    self.request_headers[i].0 = key.trim().to_string();
+                }
+            }
+            Message::UpdateHeaderValue(i, value) => {
+                if let Some(_header) = self.request_headers.get_mut(i) {
+                    self.request_headers[i].1 = value;
+                }
+            }
+            Message::PasteHeaderValue(i, value) => {
+                if let Some(header) = self.request_headers.get_mut(i) {
+                    header.1 = value.trim().to_string();
+                }
+            }
+            Message::TrimHeaderValue(i) => {
+                if let Some(header) = self.request_headers.get_mut(i) {
+                    header.1 = header.1.trim().replace('\t', " ");
+                }
+            }
+            Message::JoinHeaderLines(i) => {
+                if let Some(joined) = self
+                    .request_headers
+                    .get(i)
+                    .and_then(|(_, value)| join_wrapped_value(value))
+                {
+                    self.request_headers[i].1 = joined;
+                }
+            }
+            Message::RemoveHeaderRow(i) => {
+                if i < self.request_headers.len() {
+                    self.request_headers.remove(i);
+                }
+            }
+            Message::MoveHeaderRow(i, direction) => {
+                let target = match direction {
+                    Direction::Up => i.checked_sub(1),
+                    Direction::Down => Some(i + 1),
+                };
+                if let Some(j) = target.filter(|&j| j < self.request_headers.len()) {
+                    self.request_headers.swap(i, j);
+                }
+            }
+            Message::AddHeaderRow => {
+                self.request_headers.push((String::new(), String::new()));
+            }
+            Message::UpdateHeaderPaste(action) => {
+                self.header_paste_content.perform(action);
+            }
+            Message::ToggleHeaderPasteReplace(enabled) => {
+                self.header_paste_replace = enabled;
+            }
+            Message::ImportHeaderPaste => {
+                let headers = parse_header_block(&self.header_paste_content.text());
+                if !headers.is_empty() {
+                    if self.header_paste_replace {
+                        self.request_headers.clear();
+                    }
+                    self.request_headers.extend(headers);
+                    self.header_paste_content = text_editor::Content::new();
+                }
+            }
+            Message::ToggleFormBody(enabled) => {
+                self.request.body_source = if enabled {
+                    BodySource::Form(self.form_encoding())
+                } else {
+                    BodySource::Inline
+                };
+            }
+            Message::SetFormEncoding(encoding) => {
+                self.request.body_source = BodySource::Form(encoding);
+            }
+            Message::AddFormRow => {
+                self.request.form_fields.push(FormField::default());
+            }
+            Message::RemoveFormRow(i) => {
+                if i < self.request.form_fields.len() {
+                    self.request.form_fields.remove(i);
+                }
+            }
+            Message::UpdateFormKey(i, key) => {
+                if let Some(field) = self.request.form_fields.get_mut(i) {
+                    field.key = key;
+                }
+            }
+            Message::UpdateFormValue(i, value) => {
+                if let Some(field) = self.request.form_fields.get_mut(i) {
+                    field.value = value;
+                }
+            }
+            Message::SetFormRowType(i, kind) => {
+                if let Some(field) = self.request.form_fields.get_mut(i) {
+                    field.kind = kind;
+                }
+            }
+            Message::BrowseFormFile(i) => {
+                return Task::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().display().to_string())
+                    },
+                    move |path| match path {
+                        Some(path) => Message::UpdateFormValue(i, path),
+                        None => Message::Init,
+                    },
+                );
+            }
+            Message::Scrolled(v) => {
+                self.response_viewport = Some(v);
+                self.response_message_offset =
+                    format!("{:.0}, {:.0}", v.absolute_offset().x, v.absolute_offset().y);
+                if self.stream.is_some() {
+                    // New chunks only grow the content; a smaller offset
+                    // means the user scrolled up to read something.
+                    let y = v.absolute_offset().y;
+                    if y < self.stream_scroll_y {
+                        self.auto_scroll = false;
+                    }
+                    self.stream_scroll_y = y;
+                }
+            }
+            Message::MinimapJump(fraction) => {
+                if self.response_view == ResponseView::Text {
+                    // The editor scrolls to follow its cursor.
+                    let line =
+                        (fraction * self.response_message_content.line_count() as f32) as usize;
+                    move_cursor_to(&mut self.response_message_content, line, 0);
+                    return Task::none();
+                }
+                // Center the clicked spot when the viewport size is known.
+                return match self.response_viewport {
+                    Some(v) if v.content_bounds().height > v.bounds().height => {
+                        let (content, shown) = (v.content_bounds().height, v.bounds().height);
+                        let y = (fraction * content - shown / 2.0).clamp(0.0, content - shown);
+                        scrollable::snap_to(
+                            response_scroll_id(),
+                            RelativeOffset {
+                                x: 0.0,
+                                y: y / (content - shown),
+                            },
+                        )
+                    }
+                    _ => scrollable::snap_to(
+                        response_scroll_id(),
+                        RelativeOffset {
+                            x: 0.0,
+                            y: fraction,
+                        },
+                    ),
+                };
+            }
+            Message::Clear => {
+                self.response_message = None;
+                self.response_overview = minimap::Overview::default();
+                self.response_message_offset.clear();
+                self.method = None;
+                self.url.clear();
+                self.request_body = None;
+                self.request = self.new_request();
+                self.request_headers = self.request.header_pairs();
+                self.request_body_content = text_editor::Content::new();
+                self.body_history = UndoStack::default();
+                self.body_file_name = None;
+                self.notes_content = text_editor::Content::new();
+                self.custom_method.clear();
+            }
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> iced::Element<'_, Message> {
+        let body: iced::Element<'_, Message> = if self.side_by_side {
+            responsive(move |size| {
+                if size.width < MIN_SIDE_BY_SIDE_WIDTH {
+                    return self.stacked_view();
+                }
+                PaneGrid::new(&self.panes.0, |_, pane, _| {
+                    pane_grid::Content::new(match pane {
+                        PaneKind::Request => Scrollable::new(self.request_panel()).into(),
+                        PaneKind::Response => self.response_panel(),
+                    })
+                })
+                .spacing(10)
+                .on_resize(10, Message::PaneResized)
+                .into()
+            })
+            .into()
+        } else {
+            self.stacked_view()
+        };
+
+        let mut page = column![self.top_bar(), body];
+        if self.last_sent.is_some() || self.in_flight {
+            page = page.push(self.status_bar());
+        }
+        page.into()
+    }
+
+    /// Environment switcher and one-click buttons for the starred
+    /// collection entries.
+    fn top_bar(&self) -> iced::Element<'_, Message> {
+        let mut bar = row![].spacing(5).padding([0, 10]);
+        if !self.environments.sets.is_empty() {
+            bar = bar.push(
+                pick_list(
+                    self.environments.names(),
+                    self.environments.active.clone(),
+                    Message::SelectEnvironment,
+                )
+                .placeholder("No environment")
+                .text_size(12),
+            );
+        }
+        for (i, saved) in self.collection.iter().enumerate() {
+            if saved.starred {
+                bar = bar.push(
+                    button(text(format!("★ {}", saved.label())).size(12))
+                        .on_press(Message::LoadSaved(i)),
+                );
+            }
+        }
+        bar.into()
+    }
+
+    fn stacked_view(&self) -> iced::Element<'_, Message> {
+        column![self.request_panel(), self.response_panel()]
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn request_panel(&self) -> iced::Element<'_, Message> {
+        let method_pick_list = HttpMethod::STANDARD;
+
+        let highlighted_response = json_highlight::pretty_json_str(
+            self.response_message.as_deref().unwrap_or(""),
+            self.settings.indent,
+        );
+
+        //let response = column([text_editor(highlighted_response).into()]);
+
+        let tab_bar: iced::Element<'_, Message> = if self.compact {
+            row![
+                pick_list(Tab::ALL, Some(self.tab.clone()), Message::UpdateTab),
+                checkbox("Compact", self.compact).on_toggle(Message::ToggleCompact),
+                checkbox("Side by side", self.side_by_side).on_toggle(Message::ToggleSideBySide),
+            ]
+            .spacing(10)
+            .padding(5)
+            .into()
+        } else {
+            row![
+                radio("Closed", 0, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Auth", 1, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Headers", 2, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Body", 3, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Raw", 4, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Collection", 5, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Settings", 6, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Form", 7, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("Docs", 8, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("History", 9, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                radio("JSON-RPC", 10, self.tab.to_int(), |i| {
+                    Message::UpdateTab(Tab::from_int(i))
+                }),
+                checkbox("Compact", self.compact).on_toggle(Message::ToggleCompact),
+                checkbox("Side by side", self.side_by_side).on_toggle(Message::ToggleSideBySide),
+            ]
+            .spacing(10)
+            .padding(10)
+            .into()
+        };
+
+        let mut content = column![
+            row![
+                pick_list(
+                    method_pick_list,
+                    self.request.method.clone(),
+                    Message::UpdateMethod,
+                )
+                .placeholder("Select Method")
+                .style(|theme, status| {
+                    let mut style = pick_list::default(theme, status);
+                    if let Some(method) = &self.request.method {
+                        style.text_color = method.color();
+                    }
+                    style
+                }),
+                container(text(self.auth_badge()).size(12))
+                    .padding(5)
+                    .style(container::rounded_box),
+                text_input("Custom method", self.custom_method.as_str())
+                    .on_input(Message::UpdateCustomMethod)
+                    .width(130),
+                text_input("", self.request.url.as_str()).on_input(Message::UpdateUrl),
+                pick_list(
+                    self.variable_names(),
+                    None::<String>,
+                    Message::InsertVariableInUrl
+                )
+                .placeholder("{{var}}")
+                .width(90),
+                tooltip(
+                    text_input("host:port override", self.host_override.as_str())
+                        .on_input(Message::UpdateHostOverride)
+                        .width(180),
+                    container(
+                        text(
+                            "Connect here instead of the URL's host. Add a Host header \
+                             to send a different virtual host (HTTP/1.1; TLS SNI \
+                             still uses this host)."
+                        )
+                        .size(12)
+                    )
+                    .padding(8),
+                    tooltip::Position::Bottom,
+                )
+                .style(container::rounded_box),
+                button("Send").on_press(Message::SendRequest),
+                button(text("Copy as HTTPie").size(12)).on_press(Message::CopyAsHttpie),
+            ]
+            .spacing(10)
+            .padding(if self.compact { 5 } else { 10 }),
+        ];
+        let names = path_variable_names(&self.request.url);
+        if self.request.path_template || !names.is_empty() {
+            let mut vars = row![
+                checkbox("Path variables", self.request.path_template)
+                    .on_toggle(Message::TogglePathTemplate)
+                    .text_size(12),
+            ]
+            .spacing(10)
+            .padding([0, 10]);
+            if self.request.path_template {
+                for name in names {
+                    let value = self
+                        .request
+                        .path_vars
+                        .iter()
+                        .find(|(key, _)| *key == name)
+                        .map_or("", |(_, value)| value.as_str());
+                    let label = text(format!("{{{name}}}")).size(12);
+                    vars = vars
+                        .push(if value.is_empty() {
+                            label.color(Color::from_rgb8(255, 184, 108))
+                        } else {
+                            label
+                        })
+                        .push(
+                            text_input("value", value)
+                                .on_input(move |v| Message::UpdatePathVariable(name.clone(), v))
+                                .size(12)
+                                .width(120),
+                        );
+                }
+                let unfilled = self.request.unfilled_path_variables();
+                if !unfilled.is_empty() {
+                    vars = vars.push(
+                        text(format!("unfilled: {}", unfilled.join(", ")))
+                            .size(12)
+                            .color(Color::from_rgb8(255, 184, 108)),
+                    );
+                }
+            }
+            content = content.push(vars);
+        }
+        let url = if self.request.path_template {
+            substitute_path_variables(&self.request.url, &self.request.path_vars)
+        } else {
+            self.request.url.clone()
+        };
+        let url = env::substitute(&url, self.environments.active_vars());
+        if let Some(encoded) = encoded_url_preview(&url) {
+            content = content
+                .push(container(text(format!("🌐 Sent as {encoded}")).size(12)).padding([0, 10]));
+        }
+        let mut poll_row = row![
+            text("Poll every"),
+            text_input("5", self.poll_interval.as_str())
+                .on_input(Message::UpdatePollInterval)
+                .width(60),
+            text("s"),
+            button(if self.polling {
+                "Stop polling"
+            } else {
+                "Start polling"
+            })
+            .on_press_maybe(
+                (self.polling || self.poll_interval_duration().is_some())
+                    .then_some(Message::TogglePolling)
+            ),
+            checkbox("Stream JSON arrays", self.stream_json_arrays)
+                .on_toggle(Message::ToggleStreamJsonArrays),
+            checkbox("Auto-scroll", self.auto_scroll).on_toggle(Message::ToggleAutoScroll),
+            checkbox("Conditional GET", self.conditional_get)
+                .on_toggle(Message::ToggleConditionalGet),
+        ]
+        .spacing(10)
+        .padding([0, 10]);
+        if self.polling {
+            let now = Instant::now();
+            let status = match self.next_poll {
+                _ if self.in_flight => "sending...".to_string(),
+                Some(next) => format!(
+                    "next in {}s",
+                    next.saturating_duration_since(now).as_secs() + 1
+                ),
+                None => String::new(),
+            };
+            poll_row = poll_row.push(text(status).size(12));
+        }
+        if let Some(last) = self.last_refreshed {
+            poll_row = poll_row
+                .push(text(format!("last refreshed {}s ago", last.elapsed().as_secs())).size(12));
+        }
+        content = content.push(poll_row);
+        if self.insecure_prompt.is_some() {
+            content = content.push(
+                container(
+                    row![
+                        text("Credentials will be sent unencrypted over http://. Send anyway?")
+                            .color(Color::from_rgb8(255, 184, 108)),
+                        button("Send anyway").on_press(Message::ConfirmInsecureSend),
+                        button("Cancel").on_press(Message::CancelInsecureSend),
+                    ]
+                    .spacing(10),
+                )
+                .padding([5, 10]),
+            );
+        }
+
+        if self.compact {
+            content = content.push(tab_bar);
+        } else {
+            content = content
+                .push(horizontal_rule(20))
+                .push(tab_bar)
+                .push(horizontal_rule(50));
+        }
+
+        match self.tab {
+            Tab::None => {}
+            Tab::Auth => {
+                content = content.push(column![
+                    row![
+                        radio("No Auth", 0, self.request.auth.to_int(), |i| {
+                            Message::UpdateAuth(Auth::from_int(i))
+                        }),
+                        radio("Basic", 1, self.request.auth.to_int(), |i| {
+                            Message::UpdateAuth(Auth::from_int(i))
+                        }),
+                        radio("Bearer", 2, self.request.auth.to_int(), |i| {
+                            Message::UpdateAuth(Auth::from_int(i))
+                        }),
+                    ]
+                    .spacing(10)
+                    .padding(10),
+                    horizontal_rule(50),
+                ]);
+                if self.request.auth != Auth::None && self.request.sends_credentials_in_clear() {
+                    content = content.push(
+                        text(
+                            "⚠ This URL uses plain http://: the credentials below are sent \
+                             unencrypted. Use https:// if the server supports it.",
+                        )
+                        .color(Color::from_rgb8(255, 184, 108))
+                        .size(12),
+                    );
+                }
+                match self.request.auth {
+                    Auth::Basic => {
+                        content = content.push(
+                            column![
+                                text("Basic Authentication selected."),
+                                text_input("Username", self.request.username.as_str())
+                                    .on_input(|s| Message::UpdateUsername(s)),
+                                text_input("Password", self.request.password.as_str())
                                     .on_input(|s| Message::UpdatePassword(s)),
                             ]
                             .spacing(10)
@@ -278,89 +2780,2210 @@ impl App {
                         );
                     }
                     Auth::Bearer => {
-                        content = content.push(
-                            column![
-                                text("Bearer Authentication selected."),
+                        let mut bearer = column![
+                            text("Bearer Authentication selected."),
+                            row![
                                 text_input("Token", self.request.token.as_str())
                                     .on_input(|s| Message::UpdateToken(s)),
+                                button("Decode JWT").on_press(Message::DecodeJwt),
                             ]
-                            .spacing(10)
-                            .padding(10),
-                        );
+                            .spacing(10),
+                        ]
+                        .spacing(10)
+                        .padding(10);
+                        match &self.decoded_jwt {
+                            Some(Ok((header, payload))) => {
+                                if let Some(exp) = jwt::describe_exp(payload) {
+                                    bearer = bearer.push(text(exp));
+                                }
+                                bearer = bearer
+                                    .push(text("Header (signature not verified):").size(12))
+                                    .push(json_highlight::rich_json_value::<Message>(
+                                        header,
+                                        self.settings.indent,
+                                        self.settings.editor_font,
+                                    ))
+                                    .push(text("Payload:").size(12))
+                                    .push(json_highlight::rich_json_value::<Message>(
+                                        payload,
+                                        self.settings.indent,
+                                        self.settings.editor_font,
+                                    ));
+                            }
+                            Some(Err(e)) => {
+                                bearer = bearer
+                                    .push(text(e.clone()).color(Color::from_rgb8(255, 100, 100)));
+                            }
+                            None => {}
+                        }
+                        content = content.push(bearer);
                     }
                     Auth::None => {}
                 }
             }
-            Tab::Headers => {
-                content = content.push(
-                    column![
-                        text("Headers configuration will go here."),
-                        row![
-                            text("Key"),
-                            text("Value"),
-                            text("       "),
-                            button("Add Header +").on_press(Message::AddHeaderRow),
+            Tab::Headers => {
+                content = content.push(
+                    column![
+                        text("Headers configuration will go here."),
+                        row![
+                            text("Key"),
+                            text("Value"),
+                            text("       "),
+                            button("Add Header +").on_press(Message::AddHeaderRow),
+                        ]
+                        .spacing(10)
+                        .padding(10),
+                    ]
+                    .spacing(10)
+                    .padding(10),
+                );
+                for (i, (key, value)) in self.request_headers.iter().enumerate() {
+                    content = content.push(
+                        row![
+                            text_input("", key.as_str())
+                                .on_input(move |k| Message::UpdateHeaderKey(i, k)),
+                            text_input("", value.as_str())
+                                .on_input(move |v| Message::UpdateHeaderValue(i, v))
+                                .on_paste(move |v| Message::PasteHeaderValue(i, v)),
+                            button("↑").on_press_maybe(
+                                (i > 0).then_some(Message::MoveHeaderRow(i, Direction::Up))
+                            ),
+                            button("↓").on_press_maybe(
+                                (i + 1 < self.request_headers.len())
+                                    .then_some(Message::MoveHeaderRow(i, Direction::Down))
+                            ),
+                            button("-").on_press(Message::RemoveHeaderRow(i)),
+                        ]
+                        .spacing(10),
+                    );
+                    if let Some(problem) = header_problem(key, value) {
+                        let mut warning = row![
+                            text(problem)
+                                .size(12)
+                                .color(Color::from_rgb8(255, 100, 100))
+                        ]
+                        .spacing(10);
+                        if value.contains('\n') && join_wrapped_value(value).is_some() {
+                            warning = warning.push(
+                                button(text("Join lines").size(12))
+                                    .on_press(Message::JoinHeaderLines(i)),
+                            );
+                        }
+                        content = content.push(warning);
+                    } else if let Some(hint) = header_whitespace_hint(value) {
+                        content = content.push(
+                            row![
+                                text(format!("{hint} in the value of '{key}'"))
+                                    .size(12)
+                                    .color(Color::from_rgb8(255, 184, 108)),
+                                button(text("Trim").size(12)).on_press(Message::TrimHeaderValue(i)),
+                            ]
+                            .spacing(10),
+                        );
+                    }
+                }
+                content = content.push(
+                    column![
+                        text("Paste headers (one `Key: Value` per line):").size(12),
+                        text_editor(&self.header_paste_content)
+                            .height(100)
+                            .on_action(Message::UpdateHeaderPaste),
+                        row![
+                            checkbox("Replace existing headers", self.header_paste_replace)
+                                .on_toggle(Message::ToggleHeaderPasteReplace),
+                            button("Add pasted headers").on_press(Message::ImportHeaderPaste),
+                        ]
+                        .spacing(10),
+                    ]
+                    .spacing(5)
+                    .padding(10),
+                );
+                content = content.push(self.pre_request_view());
+            }
+            Tab::Body => {
+                let body = self.request.body.as_deref().unwrap_or("");
+                let large = body.len() > LARGE_INLINE_BODY;
+                let (validity, error_at) = if body.trim().is_empty() {
+                    (text("● empty").color(Color::from_rgb8(139, 139, 139)), None)
+                } else if large {
+                    (
+                        text("● validation paused for large bodies")
+                            .color(Color::from_rgb8(139, 139, 139)),
+                        None,
+                    )
+                } else {
+                    match serde_json::from_str::<serde_json::Value>(body) {
+                        Ok(_) => (
+                            text("✓ valid JSON").color(Color::from_rgb8(80, 250, 123)),
+                            None,
+                        ),
+                        Err(e) => (
+                            text(format!("✗ {e}")).color(Color::from_rgb8(255, 100, 100)),
+                            Some((e.line(), e.column())),
+                        ),
+                    }
+                };
+                let mut body_status = row![
+                    text(format!(
+                        "{} chars · {} bytes",
+                        body.chars().count(),
+                        body.len()
+                    ))
+                    .size(12),
+                    validity.size(12),
+                ]
+                .spacing(10);
+                if large {
+                    body_status = body_status.push(
+                        text("⚠ Large bodies slow down the editor; consider \"Load body from file\" instead")
+                            .size(12)
+                            .color(Color::from_rgb8(255, 184, 108)),
+                    );
+                }
+                if !self.body_notice.is_empty() {
+                    body_status = body_status.push(
+                        text(self.body_notice.clone())
+                            .size(12)
+                            .color(Color::from_rgb8(255, 100, 100)),
+                    );
+                }
+                if let Some((line, column)) = error_at {
+                    body_status = body_status.push(
+                        button(text("Go to error").size(12))
+                            .on_press(Message::JumpToBodyError(line, column)),
+                    );
+                }
+                let mut body_header = row![
+                    text("Request Body:"),
+                    button(text("Load body from file").size(12)).on_press(Message::BrowseBodyFile),
+                    button(text("Escape").size(12)).on_press(Message::EscapeBody),
+                    button(text("Unescape").size(12)).on_press(Message::UnescapeBody),
+                ]
+                .spacing(10);
+                if matches!(self.request.body_source, BodySource::Form(_)) {
+                    body_header =
+                        body_header.push(text("📝 Sending the fields from the Form tab").size(12));
+                }
+                if let (BodySource::Inline, Some(name)) =
+                    (&self.request.body_source, &self.body_file_name)
+                {
+                    body_header = body_header.push(text(format!("📄 Loaded from {name}")).size(12));
+                }
+                if let BodySource::File(path) = &self.request.body_source {
+                    let name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.display().to_string());
+                    let size = std::fs::metadata(path)
+                        .map(|meta| format!("{} bytes", meta.len()))
+                        .unwrap_or_else(|_| "missing".to_string());
+                    body_header = body_header
+                        .push(text(format!("📄 Sending file {name} ({size})")).size(12))
+                        .push(
+                            button(text("Use editor instead").size(12))
+                                .on_press(Message::ClearBodyFile),
+                        );
+                }
+                content = content.push(
+                    column![
+                        body_header,
+                        text_editor(&self.request_body_content)
+                            .placeholder("Type something here...")
+                            .font(self.settings.editor_font.font())
+                            .size(self.settings.editor_font.size)
+                            .on_action(Message::UpdateBody),
+                        body_status,
+                        row![
+                            checkbox(
+                                "Set Content-Type automatically",
+                                !self.request.manual_content_type
+                            )
+                            .on_toggle(Message::ToggleAutoContentType),
+                            text(match self.request.effective_content_type() {
+                                _ if self.request.manual_content_type => {
+                                    "Using the Content-Type from Headers, if any".to_string()
+                                }
+                                Some(t) => match self.request.content_type {
+                                    ContentTypeMode::Auto => format!("Detected: {}", t.mime()),
+                                    ContentTypeMode::Fixed(_) =>
+                                        format!("Sending as: {}", t.mime()),
+                                },
+                                None => "No body".to_string(),
+                            }),
+                            pick_list(
+                                ContentTypeMode::ALL,
+                                Some(self.request.content_type),
+                                Message::UpdateContentTypeMode
+                            ),
+                        ]
+                        .spacing(10),
+                        row![
+                            text("Send as:"),
+                            radio(
+                                "As typed",
+                                BodyFormat::AsTyped,
+                                Some(self.request.body_format),
+                                Message::UpdateBodyFormat
+                            ),
+                            radio(
+                                "Pretty",
+                                BodyFormat::Pretty,
+                                Some(self.request.body_format),
+                                Message::UpdateBodyFormat
+                            ),
+                            radio(
+                                "Minified",
+                                BodyFormat::Minified,
+                                Some(self.request.body_format),
+                                Message::UpdateBodyFormat
+                            ),
+                        ]
+                        .spacing(10),
+                        row![
+                            pick_list(
+                                self.snippets
+                                    .iter()
+                                    .map(|s| s.name.clone())
+                                    .collect::<Vec<_>>(),
+                                self.selected_snippet.clone(),
+                                Message::SelectSnippet
+                            )
+                            .placeholder("Snippets"),
+                            pick_list(
+                                self.variable_names(),
+                                None::<String>,
+                                Message::InsertVariableInBody
+                            )
+                            .placeholder("Insert {{var}}"),
+                            button("Insert").on_press_maybe(
+                                self.selected_snippet
+                                    .is_some()
+                                    .then_some(Message::InsertSnippet)
+                            ),
+                            button("Replace body").on_press_maybe(
+                                self.selected_snippet
+                                    .is_some()
+                                    .then_some(Message::ReplaceWithSnippet)
+                            ),
+                            button("Delete").on_press_maybe(
+                                self.selected_snippet
+                                    .is_some()
+                                    .then_some(Message::DeleteSnippet)
+                            ),
+                            text_input("Snippet name", self.snippet_name.as_str())
+                                .on_input(Message::UpdateSnippetName)
+                                .on_submit(Message::SaveSnippet)
+                                .width(160),
+                            button("Save body as snippet").on_press(Message::SaveSnippet),
+                        ]
+                        .spacing(10),
+                        row![
+                            text("Line endings:"),
+                            pick_list(
+                                LineEnding::ALL,
+                                Some(self.request.line_ending),
+                                Message::UpdateLineEnding
+                            ),
+                            text("Charset:"),
+                            text_input("e.g. utf-8", self.request.charset.as_str())
+                                .on_input(Message::UpdateCharset)
+                                .width(120),
+                        ]
+                        .spacing(10),
+                    ]
+                    .spacing(10)
+                    .padding(10),
+                );
+            }
+            Tab::Raw => {
+                content = content.push(
+                    column![
+                        text("Raw request (request line, headers, blank line, body):"),
+                        text_editor(&self.raw_request_content)
+                            .placeholder("POST https://example.com/api HTTP/1.1\nContent-Type: application/json\n\n{}")
+                            .font(self.settings.editor_font.font())
+                            .size(self.settings.editor_font.size)
+                            .height(Length::Fixed(250.0))
+                            .on_action(Message::UpdateRawRequest),
+                    ]
+                    .spacing(10)
+                    .padding(10),
+                );
+            }
+            Tab::Collection => {
+                content = content.push(self.collection_view());
+            }
+            Tab::History => {
+                content = content.push(self.history_view());
+            }
+            Tab::Form => {
+                content = content.push(self.form_view());
+            }
+            Tab::Docs => {
+                content = content.push(
+                    column![
+                        text("Notes (saved with the request):"),
+                        text_editor(&self.notes_content)
+                            .placeholder("What is this request for?")
+                            .height(200)
+                            .on_action(Message::UpdateNotes),
+                    ]
+                    .spacing(10)
+                    .padding(10),
+                );
+            }
+            Tab::JsonRpc => {
+                let mut rpc = column![
+                    text("JSON-RPC 2.0 request (written to the body as a POST):"),
+                    text_input("Method, e.g. eth_blockNumber", &self.rpc_method)
+                        .on_input(Message::UpdateRpcMethod)
+                        .on_submit(Message::BuildRpcRequest(true)),
+                    text_editor(&self.rpc_params)
+                        .placeholder("Params: [] or {} (optional)")
+                        .font(self.settings.editor_font.font())
+                        .size(self.settings.editor_font.size)
+                        .height(150)
+                        .on_action(Message::UpdateRpcParams),
+                    row![
+                        button("Write body").on_press(Message::BuildRpcRequest(false)),
+                        button("Write body and send").on_press(Message::BuildRpcRequest(true)),
+                        text(format!("Next id: {}", self.rpc_next_id.max(1))).size(12),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(10)
+                .padding(10);
+                if let Some(e) = &self.rpc_error {
+                    rpc = rpc.push(
+                        text(e.as_str())
+                            .size(12)
+                            .color(Color::from_rgb8(255, 100, 100)),
+                    );
+                }
+                content = content.push(rpc);
+            }
+            Tab::Settings => {
+                content = content.push(self.settings_view());
+            }
+        }
+
+        if !self.compact {
+            content = content.push(horizontal_rule(50));
+        }
+
+        content.into()
+    }
+
+    fn response_panel(&self) -> iced::Element<'_, Message> {
+        let mut content = column![];
+
+        if self.response.is_some() {
+            let mut toolbar = row![
+                button(text("Generate Rust types").size(12)).on_press(Message::CopyRustTypes),
+                button(text("Open in browser").size(12)).on_press(Message::OpenInBrowser),
+                button(text("Copy minified").size(12)).on_press(Message::CopyMinified),
+                button(text("Copy headers").size(12)).on_press(Message::CopyResponseHeaders),
+                button(text("Copy sent as curl").size(12))
+                    .on_press_maybe(self.last_sent.is_some().then_some(Message::CopySentAsCurl)),
+                button(text("Save as baseline").size(12)).on_press(Message::SaveBaseline),
+                button(
+                    text(if self.quick_headers {
+                        "Hide headers"
+                    } else {
+                        "Edit headers"
+                    })
+                    .size(12)
+                )
+                .on_press(Message::ToggleQuickHeaders),
+                if self.pinned_body.is_some() {
+                    button(text("Unpin").size(12)).on_press(Message::Unpin)
+                } else {
+                    button(text("Pin current").size(12)).on_press(Message::PinResponse)
+                },
+                pick_list(
+                    ResponseView::ALL,
+                    Some(self.response_view),
+                    Message::UpdateResponseView
+                )
+                .text_size(12),
+                checkbox("Raw", self.raw_body)
+                    .on_toggle(Message::ToggleRawBody)
+                    .text_size(12),
+                checkbox("Force JSON", self.force_json)
+                    .on_toggle(Message::ToggleForceJson)
+                    .text_size(12),
+                checkbox("Local times", self.local_times)
+                    .on_toggle(Message::ToggleLocalTimes)
+                    .text_size(12),
+                checkbox("Hide nulls", self.prune.hide_nulls)
+                    .on_toggle(Message::ToggleHideNulls)
+                    .text_size(12),
+                checkbox("Hide empty", self.prune.hide_empty)
+                    .on_toggle(Message::ToggleHideEmpty)
+                    .text_size(12),
+            ]
+            .spacing(10);
+            if self.settings.gist_enabled {
+                toolbar = toolbar.push(
+                    button(text("Share to Gist").size(12)).on_press_maybe(
+                        (!self.gist_sharing
+                            && self.gist_preview.is_none()
+                            && self.last_sent.is_some()
+                            && !self.gist_token.trim().is_empty())
+                        .then_some(Message::ShareGist),
+                    ),
+                );
+            }
+            if self.json_hint && !self.force_json {
+                toolbar = toolbar.push(
+                    button(text("Looks like JSON: format it").size(12))
+                        .on_press(Message::ToggleForceJson(true)),
+                );
+            }
+            if !self.response_notice.is_empty() {
+                toolbar = toolbar.push(text(self.response_notice.clone()).size(12));
+            }
+            content = content.push(toolbar);
+            if self.quick_headers {
+                content = content.push(self.quick_headers_view());
+            }
+            if let Some((_, markdown)) = &self.gist_preview {
+                content = content.push(
+                    column![
+                        text("This will be uploaded as a secret gist. Credentials are redacted, but request and response bodies are sent verbatim: check them for secrets.")
+                            .size(12)
+                            .color(Color::from_rgb8(255, 184, 108)),
+                        container(Scrollable::new(
+                            text(markdown.as_str()).font(iced::Font::MONOSPACE).size(12)
+                        ))
+                        .max_height(250),
+                        row![
+                            button(text("Upload").size(12)).on_press(Message::ConfirmGist),
+                            button(text("Cancel").size(12)).on_press(Message::CancelGist),
+                        ]
+                        .spacing(10),
+                    ]
+                    .spacing(5),
+                );
+            }
+        }
+
+        if let Some(rate_limit) = self
+            .response
+            .as_ref()
+            .and_then(|r| parse_rate_limit(&r.headers))
+        {
+            content = content.push(text(rate_limit.to_string()).size(12));
+        }
+
+        let cookies = self
+            .response
+            .as_ref()
+            .map(|r| set_cookies(&r.headers))
+            .unwrap_or_default();
+        if !cookies.is_empty() {
+            content = content
+                .push(text(format!("Set-Cookie ({}):", cookies.len())).size(12))
+                .push(cookies_view(cookies));
+        }
+
+        if let Some(response) = self.response.as_ref().filter(|r| !r.redirects.is_empty()) {
+            let original = self.last_sent.as_ref().map_or("", |r| r.url.as_str());
+            content = content.push(
+                text(format!(
+                    "Redirected: {original} → {}",
+                    response.redirects.join(" → ")
+                ))
+                .size(12),
+            );
+        }
+
+        if let Some(rpc) = &self.response_rpc {
+            content = content.push(self.rpc_response_view(rpc));
+        }
+
+        if let Some(error) = &self.response_json_error {
+            content = content.push(
+                text(format!("Invalid JSON: {error}"))
+                    .font(iced::Font::MONOSPACE)
+                    .size(12)
+                    .color(Color::from_rgb8(255, 100, 100)),
+            );
+        }
+
+        if let Some(stats) = &self.response_stats {
+            content = content.push(text(stats.to_string()).size(12));
+        }
+
+        if let Some((date, local)) = self
+            .response
+            .as_ref()
+            .filter(|_| self.local_times)
+            .and_then(|r| r.headers.get(reqwest::header::DATE)?.to_str().ok())
+            .and_then(|date| Some((date, timestamps::http_date_local(date)?)))
+        {
+            content = content.push(text(format!("Date: {date} ({local})")).size(12));
+        }
+
+        if self.response_json.is_some() || !self.path_filter.is_empty() {
+            let mut filter = row![
+                text_input(
+                    "Filter: data.items[0].name or /data/items/0/name",
+                    &self.path_filter
+                )
+                .on_input(Message::UpdatePathFilter)
+                .size(12),
+            ]
+            .spacing(10);
+            if let Some(e) = &self.path_filter_error {
+                filter = filter.push(
+                    text(e.as_str())
+                        .size(12)
+                        .color(Color::from_rgb8(255, 100, 100)),
+                );
+            }
+            content = content.push(filter);
+        }
+
+        if self.response_message.is_some() {
+            content = content.push(self.search_view());
+        }
+
+        content
+            .push(self.response_body_view())
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// JSON-RPC summary above the body: the id, then the error in red or the
+    /// highlighted result.
+    fn rpc_response_view<'a>(&self, rpc: &'a RpcResponse) -> iced::Element<'a, Message> {
+        let id = text(format!("JSON-RPC 2.0 · id {}", rpc.id())).size(12);
+        match rpc {
+            RpcResponse::Error {
+                code,
+                message,
+                data,
+                ..
+            } => {
+                let mut error = column![
+                    id,
+                    text(format!("Error {code}: {message}"))
+                        .size(14)
+                        .color(Color::from_rgb8(255, 100, 100)),
+                ]
+                .spacing(4);
+                if let Some(data) = data {
+                    error = error.push(
+                        text(data.to_string())
+                            .font(iced::Font::MONOSPACE)
+                            .size(12)
+                            .color(Color::from_rgb8(255, 100, 100)),
+                    );
+                }
+                error.into()
+            }
+            RpcResponse::Result { result, .. } => {
+                let pretty = json_highlight::to_string_indented(result, self.settings.indent)
+                    .unwrap_or_default();
+                column![
+                    id,
+                    text("Result:").size(12),
+                    container(Scrollable::new(json_highlight::rich_json_pretty_str::<
+                        Message,
+                    >(
+                        &json_highlight::wrap_at_column(&pretty, self.settings.wrap_column),
+                        json_highlight::Theme::default(),
+                        self.settings.editor_font,
+                    )))
+                    .max_height(200),
+                ]
+                .spacing(4)
+                .into()
+            }
+        }
+    }
+
+    /// Compact copy of the request headers next to the response, to change
+    /// one and resend without going back to the Headers tab.
+    fn quick_headers_view(&self) -> iced::Element<'_, Message> {
+        let mut rows = column![].spacing(4);
+        for (i, (key, value)) in self.request_headers.iter().enumerate() {
+            rows = rows.push(
+                row![
+                    text_input("Header", key.as_str())
+                        .on_input(move |k| Message::UpdateHeaderKey(i, k))
+                        .size(12)
+                        .width(200),
+                    text_input("Value", value.as_str())
+                        .on_input(move |v| Message::UpdateHeaderValue(i, v))
+                        .on_paste(move |v| Message::PasteHeaderValue(i, v))
+                        .on_submit(Message::SendRequest)
+                        .size(12),
+                    button(text("-").size(12)).on_press(Message::RemoveHeaderRow(i)),
+                ]
+                .spacing(10),
+            );
+        }
+        rows.push(
+            row![
+                button(text("+ Header").size(12)).on_press(Message::AddHeaderRow),
+                button(text("Resend").size(12))
+                    .on_press_maybe((!self.in_flight).then_some(Message::SendRequest)),
+            ]
+            .spacing(10),
+        )
+        .padding(5)
+        .into()
+    }
+
+    /// The response area: streamed elements, diff, tree or the plain text.
+    fn response_body_view(&self) -> iced::Element<'_, Message> {
+        if let Some(stream) = &self.stream {
+            // Bodies that are not a JSON array (SSE, logs, plain text) show
+            // as the text received so far.
+            if !stream.parsing {
+                return Scrollable::new(
+                    column![
+                        text(format!(
+                            "Status: {} - {} bytes received...",
+                            stream.status,
+                            stream.body.len()
+                        )),
+                        text(stream.text.as_str())
+                            .font(self.settings.editor_font.font())
+                            .size(self.settings.editor_font.size),
+                    ]
+                    .spacing(10),
+                )
+                .id(stream_scroll_id())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .on_scroll(Message::Scrolled)
+                .into();
+            }
+            let mut items = column![text(format!(
+                "Status: {} - {} elements received...",
+                stream.status,
+                stream.elements.len()
+            ))]
+            .spacing(10);
+            for element in &stream.elements {
+                let pretty = json_highlight::to_string_indented(
+                    &prune_json(element, self.prune),
+                    self.settings.indent,
+                )
+                .unwrap_or_default();
+                items = items.push(json_highlight::rich_json_pretty_str::<Message>(
+                    &json_highlight::wrap_at_column(&pretty, self.settings.wrap_column),
+                    json_highlight::Theme::default(),
+                    self.settings.editor_font,
+                ));
+            }
+            return Scrollable::new(items)
+                .id(stream_scroll_id())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .on_scroll(Message::Scrolled)
+                .into();
+        }
+
+        if self.response_message.is_none() {
+            let hint = if self.in_flight {
+                "Sending..."
+            } else {
+                "Send a request to see the response here"
+            };
+            return container(text(hint).size(16).color(Color::from_rgb8(139, 139, 139)))
+                .center(Length::Fill)
+                .into();
+        }
+
+        if self.response_view == ResponseView::Hex && self.response.is_some() {
+            return column![
+                checkbox("Include request body", self.hex_include_request)
+                    .on_toggle(Message::ToggleHexIncludeRequest)
+                    .text_size(12),
+                self.with_minimap(
+                    &self.hex_overview,
+                    Scrollable::new(
+                        text(self.response_hex.as_str())
+                            .font(iced::Font::MONOSPACE)
+                            .size(14)
+                    )
+                    .id(response_scroll_id())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .on_scroll(Message::Scrolled),
+                ),
+            ]
+            .spacing(5)
+            .into();
+        }
+
+        if let (ResponseView::DiffSent, Some(diff)) = (self.response_view, &self.sent_diff) {
+            return diff_view(diff);
+        }
+
+        if self.response_view == ResponseView::DiffBaseline {
+            let mut baseline = column![
+                text("Baseline (editable):").size(12),
+                text_editor(&self.baseline_content)
+                    .font(self.settings.editor_font.font())
+                    .size(self.settings.editor_font.size)
+                    .height(150)
+                    .on_action(Message::UpdateBaseline),
+                text_input(
+                    "Ignore paths, e.g. data.created_at, items.*.id",
+                    &self.baseline_ignore
+                )
+                .on_input(Message::UpdateBaselineIgnore)
+                .size(12),
+            ]
+            .spacing(5);
+            baseline = match &self.baseline_diff {
+                Some(diff) => baseline.push(diff_view(diff)),
+                None => baseline.push(text("Save a response as baseline to compare.").size(12)),
+            };
+            return baseline.into();
+        }
+
+        if self.response_view == ResponseView::DiffPinned {
+            let Some(diff) = &self.pinned_diff else {
+                return text("Pin a response to compare every new one against it.")
+                    .size(12)
+                    .into();
+            };
+            let mut pinned = column![
+                text(if self.pinned_changes.is_empty() {
+                    "No field changed since the pinned response.".to_string()
+                } else {
+                    format!(
+                        "{} field(s) changed since the pinned response:",
+                        self.pinned_changes.len()
+                    )
+                })
+                .size(12),
+            ]
+            .spacing(5);
+            for change in self.pinned_changes.iter().take(MAX_PINNED_CHANGES) {
+                let (line, color) = match (&change.old, &change.new) {
+                    (Some(old), Some(new)) => (
+                        format!("{}: {old} → {new}", change.path),
+                        Color::from_rgb8(255, 184, 108),
+                    ),
+                    (None, Some(new)) => (
+                        format!("{}: added {new}", change.path),
+                        Color::from_rgb8(80, 250, 123),
+                    ),
+                    (Some(old), None) => (
+                        format!("{}: removed (was {old})", change.path),
+                        Color::from_rgb8(255, 100, 100),
+                    ),
+                    (None, None) => continue,
+                };
+                pinned = pinned.push(text(line).font(iced::Font::MONOSPACE).size(12).color(color));
+            }
+            if self.pinned_changes.len() > MAX_PINNED_CHANGES {
+                pinned = pinned.push(
+                    text(format!(
+                        "… and {} more",
+                        self.pinned_changes.len() - MAX_PINNED_CHANGES
+                    ))
+                    .size(12),
+                );
+            }
+            return pinned.push(diff_view(diff)).into();
+        }
+
+        if self.response_view == ResponseView::Schema {
+            let mut check = column![
+                text("JSON Schema:").size(12),
+                text_editor(&self.schema_content)
+                    .font(self.settings.editor_font.font())
+                    .size(self.settings.editor_font.size)
+                    .height(150)
+                    .on_action(Message::UpdateSchema),
+            ]
+            .spacing(5);
+            check = match &self.schema_result {
+                None => check.push(text("Paste a schema to check the response against.").size(12)),
+                Some(Err(e)) => check.push(
+                    text(e.as_str())
+                        .size(12)
+                        .color(Color::from_rgb8(255, 100, 100)),
+                ),
+                Some(Ok(errors)) if errors.is_empty() => check.push(
+                    text("The response conforms to the schema.")
+                        .size(12)
+                        .color(Color::from_rgb8(80, 250, 123)),
+                ),
+                Some(Ok(errors)) => {
+                    let list = errors.iter().fold(column![].spacing(4), |list, error| {
+                        let path = if error.path.is_empty() {
+                            "(root)"
+                        } else {
+                            &error.path
+                        };
+                        list.push(
+                            row![
+                                text(path).font(iced::Font::MONOSPACE).size(12).width(250),
+                                text(error.message.as_str())
+                                    .size(12)
+                                    .color(Color::from_rgb8(255, 100, 100)),
+                            ]
+                            .spacing(10),
+                        )
+                    });
+                    check
+                        .push(text(format!("{} problem(s):", errors.len())).size(12))
+                        .push(
+                            Scrollable::new(list)
+                                .width(Length::Fill)
+                                .height(Length::Fill)
+                                .on_scroll(Message::Scrolled),
+                        )
+                }
+            };
+            return check.into();
+        }
+
+        if self.response_view == ResponseView::Protobuf {
+            let descriptor = self.proto_descriptor.as_ref().map_or_else(
+                || "No descriptor set loaded".to_string(),
+                |path| path.display().to_string(),
+            );
+            let names = self
+                .proto_pool
+                .as_ref()
+                .map(protobuf::message_names)
+                .unwrap_or_default();
+            let mut decoded = column![
+                row![
+                    button(text("Load descriptor set...").size(12))
+                        .on_press(Message::BrowseProtoDescriptor),
+                    text(descriptor).size(12),
+                    pick_list(names, self.proto_message.clone(), Message::SelectProtoType)
+                        .placeholder("Message type")
+                        .text_size(12),
+                ]
+                .spacing(10),
+            ]
+            .spacing(5);
+            decoded = match &self.proto_decoded {
+                // The highlighted text is 'static while the column borrows
+                // `self`; going through Element lets the lifetimes meet.
+                Some(Ok(json)) => decoded.push(iced::Element::from(
+                    Scrollable::new(json_highlight::rich_json_pretty_str::<Message>(
+                        &json_highlight::wrap_at_column(json, self.settings.wrap_column),
+                        json_highlight::Theme::default(),
+                        self.settings.editor_font,
+                    ))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .on_scroll(Message::Scrolled),
+                )),
+                Some(Err(e)) => {
+                    decoded.push(text(e.as_str()).size(12).color(Color::from_rgb8(255, 100, 100)))
+                }
+                None => decoded.push(
+                    text("Load a FileDescriptorSet (protoc --include_imports --descriptor_set_out) and pick the response type.")
+                        .size(12),
+                ),
+            };
+            return decoded.into();
+        }
+
+        if self.response_view == ResponseView::Flat {
+            return match &self.response_flat {
+                Some(flat) => self.with_minimap(
+                    &self.flat_overview,
+                    Scrollable::new(
+                        text(flat.as_str())
+                            .font(iced::Font::MONOSPACE)
+                            .size(self.settings.editor_font.size),
+                    )
+                    .id(response_scroll_id())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .on_scroll(Message::Scrolled),
+                ),
+                None => text("The response is not JSON.").size(12).into(),
+            };
+        }
+
+        if self.response_view == ResponseView::Table {
+            return match &self.response_table {
+                Some(table) => table_view(table),
+                None => text("The response is not a JSON array of objects.")
+                    .size(12)
+                    .into(),
+            };
+        }
+
+        if let (ResponseView::Tree, Some(json)) = (self.response_view, &self.response_json) {
+            return column![
+                row![
+                    text("Click a key or value to copy its path.").size(12),
+                    button(text("Expand all").size(12)).on_press(Message::ExpandAllTree),
+                    button(text("Collapse all").size(12)).on_press(Message::CollapseAllTree),
+                    text_input("depth", &self.tree_depth)
+                        .on_input(Message::UpdateTreeDepth)
+                        .on_submit(Message::ExpandTreeToDepth)
+                        .size(12)
+                        .width(60),
+                    button(text("Expand to depth").size(12)).on_press_maybe(
+                        self.tree_depth
+                            .trim()
+                            .parse::<usize>()
+                            .is_ok()
+                            .then_some(Message::ExpandTreeToDepth)
+                    ),
+                ]
+                .spacing(10),
+                Scrollable::new(json_tree::view(
+                    json,
+                    &self.tree_collapsed,
+                    Message::CopyPath,
+                    Message::ToggleTreeNode
+                ))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .on_scroll(Message::Scrolled),
+            ]
+            .into();
+        }
+
+        let editor = text_editor(&self.response_message_content)
+            .font(self.settings.editor_font.font())
+            .size(self.settings.editor_font.size)
+            .wrapping(text::Wrapping::Word) // quebra por palavra
+            .height(Length::Fill)
+            .on_action(Message::ResponseEditor);
+        self.with_minimap(&self.response_overview, editor)
+    }
+
+    /// Puts a minimap of `text` next to `body` when the text is long. The
+    /// highlighted region is the scroll viewport, or the cursor line in the
+    /// Text view, whose editor doesn't report its scroll position.
+    fn with_minimap<'a>(
+        &self,
+        overview: &'a minimap::Overview,
+        body: impl Into<iced::Element<'a, Message>>,
+    ) -> iced::Element<'a, Message> {
+        let lines = overview.line_count();
+        if lines < minimap::MIN_LINES {
+            return body.into();
+        }
+        let visible = if self.response_view == ResponseView::Text {
+            let (line, _) = self.response_message_content.cursor_position();
+            Some((line as f32 / lines as f32, 1.0 / lines as f32))
+        } else {
+            self.response_viewport.map(|v| {
+                let content = v.content_bounds().height.max(1.0);
+                (
+                    v.absolute_offset().y / content,
+                    (v.bounds().height / content).min(1.0),
+                )
+            })
+        };
+        row![
+            body.into(),
+            minimap::view(overview, visible, Message::MinimapJump)
+        ]
+        .spacing(4)
+        .into()
+    }
+
+    /// Bottom bar with the metadata of the last exchange.
+    fn status_bar(&self) -> iced::Element<'_, Message> {
+        let small = |s: String| -> iced::widget::Text<'static> { text(s).size(12) };
+        let method = match self.last_sent.as_ref().and_then(|r| r.method.as_ref()) {
+            Some(method) => small(method.to_string()).color(method.color()),
+            None => small("-".to_string()),
+        };
+        let mut bar = row![method].spacing(20);
+
+        if let Some(response) = &self.response {
+            let code = response.status.as_u16();
+            let explanation = format!(
+                "{} {}: {}",
+                code,
+                response.status.canonical_reason().unwrap_or(""),
+                describe_status(code)
+            );
+            let status_color = match code {
+                200..=299 => Color::from_rgb8(80, 250, 123),
+                300..=399 => Color::from_rgb8(139, 233, 253),
+                400..=499 => Color::from_rgb8(255, 184, 108),
+                _ => Color::from_rgb8(255, 100, 100),
+            };
+            bar = bar
+                .push(
+                    tooltip(
+                        small(format!("{} ⓘ", response.status)).color(status_color),
+                        container(text(explanation).size(12)).padding(8),
+                        tooltip::Position::Top,
+                    )
+                    .style(container::rounded_box),
+                )
+                .push(
+                    small(format!("{} ms", response.elapsed.as_millis()))
+                        .color(self.settings.timing_color(response.elapsed)),
+                )
+                .push(small(match &response.content_encoding {
+                    Some(coding) if response.wire_size > 0 => format!(
+                        "{coding}: {} → {} bytes ({:.0}% smaller)",
+                        response.wire_size,
+                        response.bytes.len(),
+                        100.0
+                            - response.wire_size as f64 * 100.0
+                                / response.bytes.len().max(1) as f64
+                    ),
+                    _ => format!("wire size: {} bytes", response.bytes.len()),
+                }));
+            if let Some(pretty_size) = self.pretty_size {
+                bar = bar.push(small(format!("pretty size: {pretty_size} bytes")));
+            }
+        } else if self.in_flight {
+            bar = bar.push(small("sending...".to_string()));
+        }
+
+        if !self.response_message_offset.is_empty() {
+            bar = bar.push(small(format!("offset {}", self.response_message_offset)));
+        }
+
+        container(bar)
+            .width(Length::Fill)
+            .padding([4, 10])
+            .style(container::rounded_box)
+            .into()
+    }
+
+    /// A blank request using the default method and auth from Settings.
+    fn new_request(&self) -> HttpRequest {
+        let mut request = HttpRequest {
+            method: Some(self.settings.default_method.clone()),
+            auth: self.settings.default_auth,
+            ..HttpRequest::default()
+        };
+        request.set_default_headers();
+        request
+    }
+
+    /// Short summary of the active auth, shown in the top bar.
+    fn auth_badge(&self) -> String {
+        match self.request.auth {
+            Auth::Bearer if self.request.token.trim().is_empty() => {
+                "🔓 Bearer (no token)".to_string()
+            }
+            Auth::Bearer => "🔒 Bearer ✓".to_string(),
+            Auth::Basic => "🔒 Basic".to_string(),
+            Auth::None => "🔓 No auth".to_string(),
+        }
+    }
+
+    /// Steps that compute `{{variables}}` right before sending.
+    fn pre_request_view(&self) -> iced::Element<'_, Message> {
+        let mut steps = column![
+            row![
+                text("Pre-request variables (inputs may use {{body}} and other variables):")
+                    .size(12),
+                button(text("Add step +").size(12)).on_press(Message::AddPreRequestStep),
+            ]
+            .spacing(10)
+        ]
+        .spacing(5)
+        .padding(10);
+        for (i, step) in self.request.pre_request.iter().enumerate() {
+            let mut line = row![
+                text_input("variable", &step.variable)
+                    .on_input(move |v| Message::UpdatePreRequestVariable(i, v))
+                    .width(120),
+                text("="),
+                pick_list(Transform::ALL, Some(step.transform), move |t| {
+                    Message::UpdatePreRequestTransform(i, t)
+                }),
+                text_input("input, e.g. {{body}}", &step.input)
+                    .on_input(move |v| Message::UpdatePreRequestInput(i, v)),
+            ]
+            .spacing(10);
+            if step.transform.needs_key() {
+                line = line.push(
+                    text_input("key", &step.key)
+                        .on_input(move |v| Message::UpdatePreRequestKey(i, v))
+                        .secure(true)
+                        .width(150),
+                );
+            }
+            steps = steps.push(line.push(button("-").on_press(Message::RemovePreRequestStep(i))));
+        }
+        steps.into()
+    }
+
+    fn form_view(&self) -> iced::Element<'_, Message> {
+        let encoding = self.form_encoding();
+        let enabled = matches!(self.request.body_source, BodySource::Form(_));
+        let mut form = column![
+            row![
+                checkbox("Send these fields as the request body", enabled)
+                    .on_toggle(Message::ToggleFormBody),
+                radio(
+                    "URL-encoded",
+                    FormEncoding::UrlEncoded,
+                    Some(encoding),
+                    Message::SetFormEncoding
+                ),
+                radio(
+                    "Multipart",
+                    FormEncoding::Multipart,
+                    Some(encoding),
+                    Message::SetFormEncoding
+                ),
+            ]
+            .spacing(10),
+            row![
+                text("Key"),
+                text("Value"),
+                text("       "),
+                button("Add Field +").on_press(Message::AddFormRow),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+        .padding(10);
+
+        for (i, field) in self.request.form_fields.iter().enumerate() {
+            let mut field_row = row![
+                text_input("", field.key.as_str()).on_input(move |k| Message::UpdateFormKey(i, k)),
+                text_input(
+                    if field.kind == FormFieldKind::File {
+                        "File path"
+                    } else {
+                        ""
+                    },
+                    field.value.as_str()
+                )
+                .on_input(move |v| Message::UpdateFormValue(i, v)),
+            ]
+            .spacing(10);
+            if encoding == FormEncoding::Multipart {
+                field_row = field_row
+                    .push(radio(
+                        "Text",
+                        FormFieldKind::Text,
+                        Some(field.kind),
+                        move |k| Message::SetFormRowType(i, k),
+                    ))
+                    .push(radio(
+                        "File",
+                        FormFieldKind::File,
+                        Some(field.kind),
+                        move |k| Message::SetFormRowType(i, k),
+                    ));
+                if field.kind == FormFieldKind::File {
+                    field_row = field_row.push(button("...").on_press(Message::BrowseFormFile(i)));
+                }
+            }
+            form = form.push(field_row.push(button("-").on_press(Message::RemoveFormRow(i))));
+        }
+
+        form.into()
+    }
+
+    fn form_encoding(&self) -> FormEncoding {
+        match self.request.body_source {
+            BodySource::Form(encoding) => encoding,
+            _ => FormEncoding::default(),
+        }
+    }
+
+    fn settings_view(&self) -> iced::Element<'_, Message> {
+        let wrap_column = match self.settings.wrap_column {
+            0 => String::new(),
+            column => column.to_string(),
+        };
+        let mut settings = column![
+            row![
+                button("Export config...").on_press(Message::ExportConfig),
+                button("Import config...").on_press(Message::ImportConfig),
+            ]
+            .spacing(10),
+            row![
+                text("New requests use"),
+                pick_list(
+                    HttpMethod::STANDARD,
+                    Some(self.settings.default_method.clone()),
+                    Message::UpdateDefaultMethod
+                ),
+                text("with auth"),
+                pick_list(
+                    Auth::ALL,
+                    Some(self.settings.default_auth),
+                    Message::UpdateDefaultAuth
+                ),
+            ]
+            .spacing(10),
+            row![
+                text("JSON indentation"),
+                pick_list(
+                    Indent::ALL,
+                    Some(self.settings.indent),
+                    Message::UpdateIndent
+                ),
+                text("Wrap response at column"),
+                text_input("off", &wrap_column)
+                    .on_input(Message::UpdateWrapColumn)
+                    .width(70),
+            ]
+            .spacing(10),
+            row![
+                text("Editor font size"),
+                slider(
+                    EditorFont::MIN_SIZE..=EditorFont::MAX_SIZE,
+                    self.settings.editor_font.size,
+                    Message::UpdateEditorFontSize
+                )
+                .width(150),
+                text(self.settings.editor_font.size.to_string()),
+                checkbox("Monospace", self.settings.editor_font.monospace)
+                    .on_toggle(Message::ToggleEditorMonospace),
+                text("Ctrl+= / Ctrl+- to zoom").size(12),
+            ]
+            .spacing(10),
+            checkbox(
+                "Log requests and responses to a file",
+                self.settings.log_enabled
+            )
+            .on_toggle(Message::ToggleLogging),
+            row![
+                text_input("Log file", self.settings.log_path.as_str())
+                    .on_input(Message::UpdateLogPath)
+                    .on_submit(Message::SaveLogPath),
+                button("Browse...").on_press(Message::BrowseLogPath),
+            ]
+            .spacing(10),
+            checkbox(
+                "Include Authorization headers and credentials in the log",
+                self.settings.log_auth
+            )
+            .on_toggle(Message::ToggleLogAuth),
+            checkbox(
+                "Enable \"Share to Gist\" (uploads the exchange with credentials redacted)",
+                self.settings.gist_enabled
+            )
+            .on_toggle(Message::ToggleGistSharing),
+        ]
+        .spacing(10)
+        .padding(10);
+
+        if self.settings.gist_enabled {
+            settings = settings.push(
+                row![
+                    text("GitHub token (gist scope)"),
+                    text_input("ghp_...", &self.gist_token)
+                        .on_input(Message::UpdateGistToken)
+                        .on_submit(Message::SaveGistToken)
+                        .secure(true),
+                    button("Save to keychain").on_press(Message::SaveGistToken),
+                ]
+                .spacing(10),
+            );
+        }
+
+        settings = settings.push(
+            row![
+                text("Response time: green under"),
+                self.number_input(NumberSetting::FastMs, "200"),
+                text("ms, red over"),
+                self.number_input(NumberSetting::SlowMs, "1000"),
+                text("ms"),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                text("Parallel runner: at most"),
+                self.number_input(NumberSetting::MaxConcurrency, "8"),
+                text("requests in flight"),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                text("Sequential runner: wait"),
+                self.number_input(NumberSetting::RunnerDelayMs, "0"),
+                text("ms between requests (a 429 with Retry-After is retried once)"),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                text("History: keep the last"),
+                self.number_input(NumberSetting::MaxHistory, "200"),
+                text("requests"),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                text("Connection pool: close idle connections after"),
+                self.number_input(NumberSetting::PoolIdleTimeout, "90"),
+                text("s (0 = never), keep at most"),
+                self.number_input(NumberSetting::PoolMaxIdle, "32"),
+                text("idle per host"),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                text("Proxy"),
+                text_input("http://proxy:8080", &self.settings.client.proxy)
+                    .on_input(Message::UpdateProxy),
+                checkbox("Use system proxy", self.settings.client.use_system_proxy)
+                    .on_toggle(Message::ToggleSystemProxy),
+                text("Accept-Encoding"),
+                pick_list(
+                    AcceptEncoding::ALL,
+                    Some(self.settings.client.accept_encoding),
+                    Message::UpdateAcceptEncoding
+                ),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                checkbox("Follow redirects", self.settings.client.follow_redirects)
+                    .on_toggle(Message::ToggleFollowRedirects),
+                text("at most"),
+                self.number_input(NumberSetting::MaxRedirects, "10"),
+                text("hops"),
+            ]
+            .spacing(10),
+        );
+
+        settings = settings.push(
+            row![
+                text("Trusted CA certificates"),
+                button("+").on_press(Message::BrowseCaCert),
+            ]
+            .spacing(10),
+        );
+        for (i, path) in self.settings.client.ca_certs.iter().enumerate() {
+            settings = settings.push(
+                row![
+                    text(path.display().to_string()).width(Length::Fill),
+                    button("-").on_press(Message::RemoveCaCert(i)),
+                ]
+                .spacing(10),
+            );
+        }
+        if !self.settings_notice.is_empty() {
+            settings = settings
+                .push(text(self.settings_notice.clone()).color(Color::from_rgb8(255, 100, 100)));
+        }
+
+        settings = settings.push(horizontal_rule(10)).push(
+            row![
+                text("Global headers (sent with every request)"),
+                button("+").on_press(Message::AddGlobalHeader),
+            ]
+            .spacing(10),
+        );
+        for (i, (key, value)) in self.settings.global_headers.iter().enumerate() {
+            settings = settings.push(
+                row![
+                    text_input("Key", key).on_input(move |k| Message::UpdateGlobalHeaderKey(i, k)),
+                    text_input("Value", value)
+                        .on_input(move |v| Message::UpdateGlobalHeaderValue(i, v)),
+                    button("-").on_press(Message::RemoveGlobalHeader(i)),
+                ]
+                .spacing(10),
+            );
+        }
+
+        settings = settings.push(horizontal_rule(10)).push(
+            row![
+                text("Environment"),
+                pick_list(
+                    self.environments.names(),
+                    self.environments.active.clone(),
+                    Message::SelectEnvironment
+                )
+                .placeholder("none"),
+                button("Delete").on_press_maybe(
+                    self.environments
+                        .active
+                        .is_some()
+                        .then_some(Message::RemoveEnvironment)
+                ),
+                text_input("New environment", &self.new_environment_name)
+                    .on_input(Message::UpdateNewEnvironmentName)
+                    .on_submit(Message::AddEnvironment)
+                    .width(160),
+                button("Add").on_press(Message::AddEnvironment),
+            ]
+            .spacing(10),
+        );
+        settings = settings.push(
+            row![
+                text("Variables (use as {{name}})"),
+                button("+").on_press(Message::AddVariable),
+            ]
+            .spacing(10),
+        );
+        for (i, (key, value)) in self.environments.active_vars().iter().enumerate() {
+            settings = settings.push(
+                row![
+                    text_input("Name", key).on_input(move |k| Message::UpdateVariableKey(i, k)),
+                    text_input("Value", value)
+                        .on_input(move |v| Message::UpdateVariableValue(i, v)),
+                    button("-").on_press(Message::RemoveVariable(i)),
+                ]
+                .spacing(10),
+            );
+        }
+
+        settings.into()
+    }
+
+    fn history_view(&self) -> iced::Element<'_, Message> {
+        let mut list = column![
+            row![
+                text(format!(
+                    "{} of at most {} entries",
+                    self.history.len(),
+                    self.settings.max_history
+                )),
+                button("Clear history")
+                    .on_press_maybe((!self.history.is_empty()).then_some(Message::ClearHistory)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+        .padding(10);
+        for (i, entry) in self.history.iter().enumerate().rev() {
+            let method = match &entry.request.method {
+                Some(method) => text(method.to_string()).color(method.color()),
+                None => text(""),
+            };
+            let status = entry
+                .status
+                .map_or_else(|| "failed".to_string(), |code| code.to_string());
+            list = list.push(
+                row![
+                    text(entry.sent_at.clone()).size(12).width(140),
+                    method.width(70),
+                    text(status).width(50),
+                    text(entry.request.url.clone()).width(Length::Fill),
+                    button("Load").on_press(Message::LoadHistory(i)),
+                    button("-").on_press(Message::RemoveHistory(i)),
+                ]
+                .spacing(10),
+            );
+        }
+        list.into()
+    }
+
+    fn collection_view(&self) -> iced::Element<'_, Message> {
+        let mut list = column![
+            row![
+                text_input("Name", self.collection_name.as_str())
+                    .on_input(Message::UpdateCollectionName),
+                button("Save current").on_press(Message::SaveToCollection),
+                button(if self.running_all {
+                    "Sending..."
+                } else {
+                    "Send all (parallel)"
+                })
+                .on_press_maybe(
+                    (!self.running_all && !self.collection.is_empty())
+                        .then_some(Message::SendAllParallel)
+                ),
+                button("Run in sequence").on_press_maybe(
+                    (!self.running_all && !self.collection.is_empty())
+                        .then_some(Message::SendAllSequential)
+                ),
+            ]
+            .spacing(10),
+            checkbox(
+                "Store auth secrets in the OS keychain",
+                self.settings.use_keychain
+            )
+            .on_toggle(Message::ToggleKeychain),
+        ]
+        .spacing(5)
+        .padding(10);
+
+        list = list.push(
+            row![
+                text_input("Paste a patchlite:// link", self.share_link.as_str())
+                    .on_input(Message::UpdateShareLink)
+                    .on_submit(Message::ImportShareLink),
+                button("Import link").on_press(Message::ImportShareLink),
+                button("Copy share link").on_press(Message::CopyShareLink),
+                button("Import file...").on_press(Message::BrowseBatchImport),
+                button("Import Postman...").on_press(Message::ImportPostman),
+                button("Export to Postman...").on_press_maybe(
+                    (!self.collection.is_empty()).then_some(Message::ExportPostman)
+                ),
+            ]
+            .spacing(10),
+        );
+        if !self.collection_status.is_empty() {
+            list = list.push(text(self.collection_status.clone()).size(12));
+        }
+
+        for (i, saved) in self.collection.iter().enumerate() {
+            let method = match &saved.request.method {
+                Some(method) => text(method.to_string()).color(method.color()),
+                None => text(""),
+            };
+            list = list.push(
+                row![
+                    method.width(70),
+                    match saved.request.notes.lines().next() {
+                        Some(description) if !description.trim().is_empty() => column![
+                            text(saved.label().to_string()),
+                            text(description.to_string())
+                                .size(12)
+                                .color(Color::from_rgb8(139, 139, 139)),
                         ]
-                        .spacing(10)
-                        .padding(10),
+                        .width(Length::Fill),
+                        _ => column![text(saved.label().to_string())].width(Length::Fill),
+                    },
+                    button(if saved.starred { "★" } else { "☆" })
+                        .style(button::text)
+                        .on_press(Message::ToggleStarred(i)),
+                    button("Load").on_press(Message::LoadSaved(i)),
+                    button("-").on_press(Message::RemoveSaved(i)),
+                ]
+                .spacing(10),
+            );
+        }
+
+        if let Some(summary) = &self.run_summary {
+            list = list.push(horizontal_rule(10)).push(text(format!(
+                "2xx: {} · 3xx: {} · 4xx: {} · 5xx: {} · errors: {} · total: {} ms",
+                summary.count_class(2),
+                summary.count_class(3),
+                summary.count_class(4),
+                summary.count_class(5),
+                summary.count_errors(),
+                summary.total.as_millis()
+            )));
+            for result in &summary.results {
+                let status = match &result.outcome {
+                    Ok(status) => status.to_string(),
+                    Err(e) => e.lines().next().unwrap_or_default().to_string(),
+                };
+                list = list.push(
+                    row![
+                        text(result.name.clone()).width(Length::Fill),
+                        text(status).width(Length::Fill),
+                        text(format!("{} ms", result.elapsed.as_millis()))
+                            .color(self.settings.timing_color(result.elapsed))
+                            .width(80),
                     ]
-                    .spacing(10)
-                    .padding(10),
+                    .spacing(10),
                 );
-                for (i, (key, value)) in self.request_headers.iter().enumerate() {
-                    content = content.push(
-                        row![
-                            text_input("", key.as_str())
-                                .on_input(move |k| Message::UpdateHeaderKey(i, k)),
-                            text_input("", value.as_str())
-                                .on_input(move |v| Message::UpdateHeaderValue(i, v)),
-                            button("-").on_press(Message::RemoveHeaderRow(i)),
-                        ]
-                        .spacing(10),
-                    );
-                }
             }
-            Tab::Body => {
-                content = content.push(
-                    column![
-                        text("Request Body:"),
-                        text_editor(&self.request_body_content)
-                            .placeholder("Type something here...")
-                            .on_action(Message::UpdateBody),
-                    ]
-                    .spacing(10)
-                    .padding(10),
-                );
+            if !summary.results.is_empty() {
+                list = list.push(timeline::view(summary, &self.settings));
             }
         }
 
-        content = content.push(horizontal_rule(50));
+        list.into()
+    }
 
-        content = content.push(
-            column![
-                text_editor(&self.response_message_content)
-                    .wrapping(text::Wrapping::Word) // quebra por palavra
-                    .width(1000.0)
-                    .height(Length::Fixed(1000.0))
-                    .on_action(Message::ResponseEditor),
-            ]
-            .spacing(20),
-        );
+    fn number_setting(&self, setting: NumberSetting) -> u64 {
+        match setting {
+            NumberSetting::MaxHistory => self.settings.max_history as u64,
+            NumberSetting::MaxRedirects => self.settings.client.max_redirects as u64,
+            NumberSetting::MaxConcurrency => self.settings.max_concurrency as u64,
+            NumberSetting::PoolIdleTimeout => self.settings.client.pool_idle_timeout_secs,
+            NumberSetting::PoolMaxIdle => self.settings.client.pool_max_idle_per_host as u64,
+            NumberSetting::FastMs => self.settings.fast_ms,
+            NumberSetting::SlowMs => self.settings.slow_ms,
+            NumberSetting::RunnerDelayMs => self.settings.runner_delay_ms,
+        }
+    }
+
+    fn set_number_setting(&mut self, setting: NumberSetting, value: u64) {
+        match setting {
+            NumberSetting::MaxHistory => {
+                self.settings.max_history = value as usize;
+                history::trim(&mut self.history, self.settings.max_history);
+                self.save_history();
+            }
+            NumberSetting::MaxRedirects => self.settings.client.max_redirects = value as usize,
+            NumberSetting::MaxConcurrency => self.settings.max_concurrency = value as usize,
+            NumberSetting::PoolIdleTimeout => self.settings.client.pool_idle_timeout_secs = value,
+            NumberSetting::PoolMaxIdle => {
+                self.settings.client.pool_max_idle_per_host = value as usize
+            }
+            NumberSetting::FastMs => self.settings.fast_ms = value,
+            NumberSetting::SlowMs => self.settings.slow_ms = value,
+            NumberSetting::RunnerDelayMs => self.settings.runner_delay_ms = value,
+        }
+        self.save_settings();
+    }
 
-        //content = content.push(row![button("Clear").on_press(Message::Clear),]);
+    /// Text field for a number setting, showing the draft while one exists.
+    fn number_input(
+        &self,
+        setting: NumberSetting,
+        placeholder: &str,
+    ) -> iced::widget::TextInput<'_, Message> {
+        let value = self
+            .number_drafts
+            .get(&setting)
+            .cloned()
+            .unwrap_or_else(|| self.number_setting(setting).to_string());
+        text_input(placeholder, &value)
+            .on_input(move |text| Message::EditNumberSetting(setting, text))
+            .on_submit(Message::SubmitNumberSetting(setting))
+            .width(70)
+    }
 
-        content.into()
+    fn subscription(&self) -> Subscription<Message> {
+        let polling = if self.polling {
+            iced::time::every(Duration::from_secs(1)).map(Message::PollTick)
+        } else {
+            Subscription::none()
+        };
+        let shortcuts = if self.tab == Tab::Body {
+            iced::event::listen_with(|event, _status, _window| body_shortcut(event))
+        } else {
+            Subscription::none()
+        };
+        let zoom = iced::event::listen_with(|event, _status, _window| zoom_shortcut(event));
+        let file_drop = iced::event::listen_with(|event, _status, _window| match event {
+            iced::Event::Window(iced::window::Event::FileDropped(path)) => {
+                Some(Message::BodyFileDropped(path))
+            }
+            _ => None,
+        });
+        let flush = if self.history_dirty || self.settings_dirty || self.environments_dirty {
+            iced::time::every(history::SAVE_INTERVAL).map(|_| Message::Flush)
+        } else {
+            Subscription::none()
+        };
+        let close = iced::window::close_requests().map(Message::CloseRequested);
+        Subscription::batch([polling, shortcuts, zoom, file_drop, flush, close])
+    }
+
+    /// The shared client, rebuilt only when the connection settings changed
+    /// so pooled connections are reused between sends.
+    fn http_client(&mut self) -> Result<HttpClient, SendError> {
+        if let Some(client) = self
+            .http_client
+            .as_ref()
+            .filter(|client| client.options == self.settings.client)
+        {
+            return Ok(client.clone());
+        }
+        let client = HttpClient::new(&self.settings.client)?;
+        self.http_client = Some(client.clone());
+        Ok(client)
+    }
+
+    fn poll_interval_duration(&self) -> Option<Duration> {
+        self.poll_interval
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Stops polling and cancels the poll request still in flight, if any.
+    fn stop_polling(&mut self) {
+        if self.polling {
+            self.cancel_in_flight();
+        }
+        self.polling = false;
+        self.next_poll = None;
+    }
+
+    /// Aborts the request in flight, if any; a response already on its way
+    /// is ignored because its generation is stale.
+    fn cancel_in_flight(&mut self) {
+        if let Some(handle) = self.in_flight_handle.take() {
+            handle.abort();
+        }
+        self.request_generation += 1;
+        self.in_flight = false;
+        self.stream = None;
+    }
+
+    fn selected_snippet(&self) -> Option<&Snippet> {
+        let name = self.selected_snippet.as_ref()?;
+        self.snippets.iter().find(|s| &s.name == name)
+    }
+
+    /// Collection entries prepared like a manual send. Fails (naming the
+    /// entry) instead of running a request that can't be prepared.
+    fn runner_requests(&self) -> Result<Vec<SavedRequest>, String> {
+        self.collection
+            .iter()
+            .cloned()
+            .map(|mut saved| {
+                self.prepare_for_send(&mut saved.request)
+                    .map_err(|e| format!("{}: {e}", saved.label()))?;
+                Ok(saved)
+            })
+            .collect()
+    }
+
+    /// Turns a request as edited into the one that goes on the wire: the
+    /// automatic Content-Type, variables, host override and URL encoding,
+    /// and the global headers.
+    fn prepare_for_send(&self, request: &mut HttpRequest) -> Result<(), String> {
+        request.apply_content_type();
+        request.resolve_variables(self.environments.active_vars());
+        request.url = self.resolve_url(&request.url)?;
+        request.merge_global_headers(&self.settings.global_headers);
+        Ok(())
+    }
+
+    /// Adds the last sent request to the history; it is written out by the
+    /// next `Flush`.
+    fn record_history(&mut self, status: Option<u16>) {
+        let Some(sent) = &self.sent_template else {
+            return;
+        };
+        let entry = HistoryEntry {
+            request: history::scrubbed(sent),
+            status,
+            sent_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        };
+        history::push(&mut self.history, entry, self.settings.max_history);
+        self.history_dirty = true;
+    }
+
+    fn save_history(&mut self) {
+        self.history_dirty = false;
+        if let Err(e) = storage::save(HISTORY_FILE, &self.history) {
+            self.collection_status = e;
+        }
+    }
+
+    fn save_collection(&mut self) {
+        if !self.settings.use_keychain {
+            if let Err(e) = storage::save(COLLECTION_FILE, &self.collection) {
+                self.collection_status = e;
+            }
+            return;
+        }
+
+        // Secrets go to the keychain; if it is unavailable they only live in
+        // memory for this session, never in the file.
+        let mut redacted = Vec::with_capacity(self.collection.len());
+        for saved in &self.collection {
+            if let Err(e) = secrets::store(&saved.id, &saved.request) {
+                self.collection_status = format!("{e}. Secrets are kept in memory only.");
+            }
+            redacted.push(SavedRequest {
+                request: secrets::redacted(&saved.request),
+                ..saved.clone()
+            });
+        }
+        if let Err(e) = storage::save(COLLECTION_FILE, &redacted) {
+            self.collection_status = e;
+        }
+    }
+
+    /// Writes the last sent request and its outcome to the log file, if
+    /// logging is enabled. Returns the error to show when that fails.
+    fn log_exchange(&self, outcome: Result<&HttpResponse, &str>) -> Option<String> {
+        if !self.settings.log_enabled || self.settings.log_path.is_empty() {
+            return None;
+        }
+        let request = self.last_sent.as_ref()?;
+        logging::log_exchange(
+            std::path::Path::new(&self.settings.log_path),
+            request,
+            outcome,
+            self.settings.log_auth,
+        )
+        .err()
+    }
+
+    /// Writes out whatever was only changed in memory so far.
+    fn flush(&mut self) {
+        if self.history_dirty {
+            self.save_history();
+        }
+        if self.settings_dirty {
+            self.save_settings();
+        }
+        if self.environments_dirty {
+            self.save_environments();
+        }
+    }
+
+    fn save_settings(&mut self) {
+        self.settings_dirty = false;
+        if let Err(e) = storage::save(SETTINGS_FILE, &self.settings) {
+            self.collection_status = e;
+        }
+    }
+
+    fn save_environments(&mut self) {
+        self.environments_dirty = false;
+        if let Err(e) = storage::save(env::ENV_FILE, &self.environments) {
+            self.settings_notice = e;
+        }
+    }
+
+    /// Names offered by the "insert variable" pickers.
+    fn variable_names(&self) -> Vec<String> {
+        self.environments
+            .active_vars()
+            .iter()
+            .map(|(key, _)| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .collect()
+    }
+
+    fn save_snippets(&mut self) {
+        if let Err(e) = storage::save(SNIPPETS_FILE, &self.snippets) {
+            self.collection_status = e;
+        }
+    }
+
+    fn load_request(&mut self, request: HttpRequest) {
+        self.stop_polling();
+        self.custom_method = match &request.method {
+            Some(HttpMethod::Custom(method)) => method.clone(),
+            _ => String::new(),
+        };
+        self.request_headers = request.header_pairs();
+        self.request_body_content =
+            text_editor::Content::with_text(request.body.as_deref().unwrap_or(""));
+        self.body_history = UndoStack::default();
+        self.body_file_name = None;
+        self.notes_content = text_editor::Content::with_text(&request.notes);
+        self.request = request;
+    }
+
+    // Applies the host override (if any) and percent-encodes the URL.
+    fn resolve_url(&self, url: &str) -> Result<String, String> {
+        let url = if self.host_override.trim().is_empty() {
+            url.to_string()
+        } else {
+            override_authority(url, &self.host_override)?
+        };
+        normalize_url(&url)
+    }
+
+    /// Re-renders the response text and tree from the current response,
+    /// applying the indentation and null/empty filters.
+    fn refresh_response_display(&mut self) {
+        let Some(response) = &self.response else {
+            return;
+        };
+        // JSON is rendered when declared, forced, or when a text/plain body
+        // clearly is JSON and the user accepted the hint.
+        self.json_hint = !response.has_json_content_type() && looks_like_json(&response.body);
+        let parsed = if self.force_json {
+            parse_json_lenient(&response.body)
+        } else if response.has_json_content_type() {
+            serde_json::from_str::<serde_json::Value>(&response.body).ok()
+        } else {
+            None
+        };
+        self.response_json_error = if response.has_json_content_type() && parsed.is_none() {
+            serde_json::from_str::<serde_json::Value>(&response.body)
+                .err()
+                .map(|e| {
+                    format!(
+                        "{e}\n{}",
+                        json_highlight::error_caret(&response.body, e.line(), e.column())
+                    )
+                })
+        } else {
+            None
+        };
+        self.response_rpc = parsed.as_ref().and_then(jsonrpc::detect);
+        self.response_json = parsed.map(|value| prune_json(&value, self.prune));
+        self.path_filter_error = None;
+        if let Some(json) = &self.response_json {
+            match select_path(json, &self.path_filter).cloned() {
+                Ok(selected) => self.response_json = Some(selected),
+                Err(e) => self.path_filter_error = Some(e),
+            }
+        }
+        self.response_table = self.response_json.as_ref().and_then(json_table);
+        self.response_flat = self.response_json.as_ref().map(|json| {
+            flatten_json(json)
+                .into_iter()
+                .map(|(path, value)| format!("{path} = {value}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        let body = match &self.response_json {
+            Some(value) => json_highlight::to_string_indented(value, self.settings.indent)
+                .unwrap_or_else(|| response.body.clone()),
+            None if response.has_form_content_type() => response.pretty_form_body(),
+            None => response.body.clone(),
+        };
+        self.pretty_size = self.response_json.is_some().then_some(body.len());
+        let body = if self.local_times && self.response_json.is_some() {
+            timestamps::annotate_timestamps(&body)
+        } else {
+            body
+        };
+        self.response_hex = hex::hexdump(&response.bytes);
+        if self.hex_include_request
+            && let Some(sent) = self.last_sent.as_ref().and_then(|r| r.body.as_deref())
+        {
+            self.response_hex = format!(
+                "Request body:\n{}\nResponse body:\n{}",
+                hex::hexdump(sent.as_bytes()),
+                self.response_hex
+            );
+        }
+        self.hex_overview = minimap::Overview::new(&self.response_hex);
+        self.flat_overview = self
+            .response_flat
+            .as_deref()
+            .map(minimap::Overview::new)
+            .unwrap_or_default();
+        // The captured body is never modified, so raw mode shows the exact
+        // text the server sent.
+        let body = if self.raw_body {
+            response.body.clone()
+        } else {
+            json_highlight::wrap_at_column(&body, self.settings.wrap_column)
+        };
+        let text = format!("Status: {}\nBody:\n{}", response.status, body);
+        self.set_response_text(text);
+    }
+
+    /// Diff between the body of the last sent request and `received`.
+    fn diff_sent_body(&self, received: &str) -> Option<Result<Vec<DiffLine>, String>> {
+        let sent = self.last_sent.as_ref()?.body.as_deref()?;
+        if sent.trim().is_empty() {
+            return None;
+        }
+        let sent = normalize_json(sent).map_err(|e| format!("The sent body is not JSON: {e}"));
+        let received =
+            normalize_json(received).map_err(|e| format!("The response body is not JSON: {e}"));
+        Some(match (sent, received) {
+            (Ok(sent), Ok(received)) => Ok(line_diff(&sent, &received)),
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        })
+    }
+
+    /// Diffs the current response against the baseline, minus ignored paths.
+    fn refresh_baseline_diff(&mut self) {
+        let baseline = self.baseline_content.text();
+        self.baseline_diff = match &self.response {
+            Some(response) if !baseline.trim().is_empty() => Some(Ok(diff_ignoring(
+                &baseline,
+                &response.body,
+                &parse_ignore_paths(&self.baseline_ignore),
+            ))),
+            _ => None,
+        };
+    }
+
+    /// Diffs the current response against the pinned one and lists the JSON
+    /// fields whose value changed.
+    fn refresh_pinned_diff(&mut self) {
+        let (Some(pinned), Some(response)) = (&self.pinned_body, &self.response) else {
+            self.pinned_diff = None;
+            self.pinned_changes.clear();
+            return;
+        };
+        self.pinned_diff = Some(Ok(diff_ignoring(pinned, &response.body, &[])));
+        self.pinned_changes = match (
+            serde_json::from_str::<serde_json::Value>(pinned),
+            serde_json::from_str::<serde_json::Value>(&response.body),
+        ) {
+            (Ok(old), Ok(new)) => changed_fields(&old, &new),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Decodes the response bytes with the selected Protobuf message type.
+    fn refresh_proto_decoded(&mut self) {
+        self.proto_decoded = match (&self.response, &self.proto_pool, &self.proto_message) {
+            (Some(response), Some(pool), Some(name)) => Some(
+                protobuf::decode_to_json(pool, name, &response.bytes).map(|value| {
+                    json_highlight::to_string_indented(&value, self.settings.indent)
+                        .unwrap_or_default()
+                }),
+            ),
+            _ => None,
+        };
+    }
+
+    /// Validates the current response against the schema in the Schema view.
+    fn refresh_schema_check(&mut self) {
+        let schema = self.schema_content.text();
+        self.schema_result = match &self.response {
+            Some(_) if schema.trim().is_empty() => None,
+            Some(response) => Some(match parse_json_lenient(&response.body) {
+                Some(value) => validate_schema(&schema, &value),
+                None => Err("The response body is not JSON.".to_string()),
+            }),
+            None => None,
+        };
+    }
+
+    /// Replaces the body editor content and switches back to an inline body.
+    fn set_body_text(&mut self, body: String) {
+        let before = self.request_body_content.text();
+        self.body_history.record(&before, true);
+        self.restore_body_text(body);
+    }
+
+    /// Like `set_body_text`, without recording an undo step.
+    fn restore_body_text(&mut self, body: String) {
+        self.request_body_content = text_editor::Content::with_text(&body);
+        self.request.body = Some(body);
+        self.request.body_source = BodySource::Inline;
+        self.body_notice.clear();
+        self.body_file_name = None;
+    }
+
+    fn set_response_text(&mut self, text: String) {
+        self.response_message_content = text_editor::Content::with_text(text.as_str());
+        self.response_overview = minimap::Overview::new(&text);
+        self.response_message = Some(text);
+        self.refresh_search();
+    }
+
+    fn refresh_search(&mut self) {
+        match find_matches(
+            &self.response_message_content.text(),
+            &self.search_query,
+            self.search_regex,
+        ) {
+            Ok(matches) => {
+                self.search_matches = matches;
+                self.search_error = None;
+            }
+            Err(e) => {
+                self.search_matches.clear();
+                self.search_error = Some(e);
+            }
+        }
+    }
+
+    /// Search box and the clickable list of matches.
+    fn search_view(&self) -> iced::Element<'_, Message> {
+        let mut search = row![
+            text_input("Search response", &self.search_query)
+                .on_input(Message::UpdateSearch)
+                .size(12)
+                .width(250),
+            checkbox("Regex", self.search_regex)
+                .on_toggle(Message::ToggleSearchRegex)
+                .text_size(12),
+        ]
+        .spacing(10);
+        if let Some(e) = &self.search_error {
+            return search
+                .push(
+                    text(e.clone())
+                        .size(12)
+                        .color(Color::from_rgb8(255, 100, 100)),
+                )
+                .into();
+        }
+        let matches = &self.search_matches;
+        if self.search_query.is_empty() {
+            return search.into();
+        }
+        search = search.push(
+            text(if matches.len() >= MAX_MATCHES {
+                format!("{MAX_MATCHES}+ matches")
+            } else {
+                format!("{} matches", matches.len())
+            })
+            .size(12),
+        );
+
+        let highlight = Color::from_rgb8(255, 184, 108);
+        let group_colors = [
+            Color::from_rgb8(139, 233, 253),
+            Color::from_rgb8(189, 147, 249),
+            Color::from_rgb8(80, 250, 123),
+        ];
+        let mono = |s: String| -> iced::widget::Text<'static> {
+            text(s).font(iced::Font::MONOSPACE).size(12)
+        };
+        let mut list = column![].spacing(2);
+        for (i, found) in matches.iter().enumerate() {
+            let mut line = row![
+                mono(format!("{:>5}: ", found.line + 1)).color(Color::from_rgb8(139, 139, 139)),
+                mono(found.line_text[..found.start].trim_start().to_string()),
+                mono(found.line_text[found.start..found.end].to_string()).color(highlight),
+                mono(found.line_text[found.end..].to_string()),
+            ];
+            for (n, group) in found.groups.iter().enumerate() {
+                if let Some(group) = group {
+                    line = line.push(
+                        mono(format!("  ${}={group}", n + 1))
+                            .color(group_colors[n % group_colors.len()]),
+                    );
+                }
+            }
+            list = list.push(
+                button(line.wrap())
+                    .padding(0)
+                    .style(button::text)
+                    .on_press(Message::JumpToMatch(i)),
+            );
+        }
+        column![search, Scrollable::new(list).height(Length::Fixed(120.0))]
+            .spacing(5)
+            .into()
     }
 
     fn new() -> (Self, Task<Message>) {
         let mut app = Self::default();
-        app.request.set_default_headers();
-        app.request_headers = app
-            .request
-            .headers
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.to_str().unwrap().to_string()))
-            .collect();
+        let mut load_errors = Vec::new();
+        app.settings = storage::load_or_default(SETTINGS_FILE, &mut load_errors);
+        app.request = app.new_request();
+        app.request_headers = app.request.header_pairs();
+        app.collection = storage::load_or_default(COLLECTION_FILE, &mut load_errors);
+        let new_ids = collection::ensure_ids(&mut app.collection);
+        if app.settings.use_keychain {
+            for saved in &mut app.collection {
+                if let Err(e) = secrets::fetch(&saved.id, &mut saved.request) {
+                    app.collection_status = e;
+                }
+            }
+        }
+        if new_ids {
+            app.save_collection();
+        }
+        app.snippets = storage::load_or_default(SNIPPETS_FILE, &mut load_errors);
+        app.history = storage::load_or_default(HISTORY_FILE, &mut load_errors);
+        if app.settings.gist_enabled {
+            app.gist_token = secrets::fetch_value(gist::TOKEN_ENTRY).unwrap_or_default();
+        }
+        // Older versions could store 0, which would empty the history.
+        if app.settings.max_history == 0 {
+            app.settings.max_history = Settings::default().max_history;
+        }
+        history::trim(&mut app.history, app.settings.max_history);
+        app.environments = storage::load_or_default(env::ENV_FILE, &mut load_errors);
+        if !load_errors.is_empty() {
+            load_errors.push(app.collection_status.clone());
+            app.collection_status = load_errors.join("\n").trim_end().to_string();
+        }
         let task = Task::perform(async {}, |_| Message::Init);
         (app, task)
     }
@@ -0,0 +1,133 @@
+use iced::widget::canvas::{self, Frame, Geometry};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, event, mouse};
+
+pub const WIDTH: f32 = 60.0;
+
+/// Responses shorter than this fit on a screen or two and get no minimap.
+pub const MIN_LINES: usize = 100;
+
+/// Line lengths of a text, measured once when the text changes, and the
+/// cached drawing of them.
+#[derive(Default)]
+pub struct Overview {
+    lines: Vec<usize>,
+    cache: canvas::Cache,
+}
+
+impl Overview {
+    pub fn new(text: &str) -> Self {
+        Overview {
+            lines: text.lines().map(|l| l.trim_end().len()).collect(),
+            cache: canvas::Cache::new(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// Thin overview of a text: one bar per line (or per group of lines when
+/// there are more lines than pixels), as long as the line. `visible` is the
+/// shown region as `(start, length)` fractions of the whole text. A click
+/// emits `on_jump` with the fraction of the text under the cursor.
+pub fn view<'a, Message: 'a>(
+    overview: &'a Overview,
+    visible: Option<(f32, f32)>,
+    on_jump: fn(f32) -> Message,
+) -> Element<'a, Message> {
+    canvas::Canvas::new(Minimap {
+        overview,
+        visible,
+        on_jump,
+    })
+    .width(WIDTH)
+    .height(Length::Fill)
+    .into()
+}
+
+struct Minimap<'a, Message> {
+    overview: &'a Overview,
+    visible: Option<(f32, f32)>,
+    on_jump: fn(f32) -> Message,
+}
+
+impl<Message> canvas::Program<Message> for Minimap<'_, Message> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        if let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
+            && let Some(position) = cursor.position_in(bounds)
+        {
+            let fraction = (position.y / bounds.height.max(1.0)).clamp(0.0, 1.0);
+            return (event::Status::Captured, Some((self.on_jump)(fraction)));
+        }
+        (event::Status::Ignored, None)
+    }
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        // The bars only change with the text or the size; the cache redraws
+        // them when the bounds change.
+        let bars = self.overview.cache.draw(renderer, bounds.size(), |frame| {
+            frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color::from_rgb8(30, 30, 30));
+            let lines = &self.overview.lines;
+            let rows = bounds.height.max(1.0) as usize;
+            let per_row = lines.len().div_ceil(rows).max(1);
+            let row_height = bounds.height / lines.len().div_ceil(per_row).max(1) as f32;
+            // Lines past 120 characters all draw at full width.
+            let scale = bounds.width / 120.0;
+            let ink = Color::from_rgb8(139, 139, 139);
+            for (i, group) in lines.chunks(per_row).enumerate() {
+                let longest = group.iter().copied().max().unwrap_or_default();
+                if longest == 0 {
+                    continue;
+                }
+                frame.fill_rectangle(
+                    Point::new(0.0, i as f32 * row_height),
+                    Size::new(
+                        (longest as f32 * scale).min(bounds.width),
+                        row_height.clamp(1.0, 3.0),
+                    ),
+                    ink,
+                );
+            }
+        });
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        if let Some((start, length)) = self.visible {
+            frame.fill_rectangle(
+                Point::new(0.0, start * bounds.height),
+                Size::new(bounds.width, (length * bounds.height).max(2.0)),
+                Color::from_rgba8(255, 255, 255, 0.15),
+            );
+        }
+
+        vec![bars, frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &(),
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
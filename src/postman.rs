@@ -0,0 +1,430 @@
+use crate::collection::SavedRequest;
+use crate::request::{
+    Auth, BodySource, FormEncoding, FormField, FormFieldKind, HttpMethod, HttpRequest,
+};
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use serde_json::{Value, json};
+
+pub const POSTMAN_EXPORT_FILE: &str = "patch-lite.postman_collection.json";
+
+const SCHEMA_V21: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Renders `requests` as a Postman Collection v2.1 document.
+pub fn export_postman(name: &str, requests: &[SavedRequest]) -> String {
+    let items: Vec<Value> = requests
+        .iter()
+        .map(|saved| {
+            json!({
+                "name": saved.label(),
+                "request": postman_request(&saved.request),
+            })
+        })
+        .collect();
+    let collection = json!({
+        "info": { "name": name, "schema": SCHEMA_V21 },
+        "item": items,
+    });
+    serde_json::to_string_pretty(&collection).unwrap_or_default()
+}
+
+/// What a Postman collection file turned into: its requests (folders
+/// flattened into "Folder / Request" names) and its collection variables.
+#[derive(Default)]
+pub struct PostmanImport {
+    pub name: String,
+    pub requests: Vec<SavedRequest>,
+    pub variables: Vec<(String, String)>,
+}
+
+/// Parses a Postman Collection v2.0/v2.1 document. Postman writes variables
+/// as `{{name}}` like we do, so URLs, headers and bodies are kept verbatim
+/// and resolve against the environment built from `variables`. Auth set on
+/// the collection or a folder applies to requests that don't set their own.
+pub fn from_postman(json: &str) -> Result<PostmanImport, String> {
+    let doc: Value =
+        serde_json::from_str(json).map_err(|e| format!("Not a Postman collection: {e}"))?;
+    let items = doc
+        .get("item")
+        .and_then(Value::as_array)
+        .ok_or("Not a Postman collection: missing `item` list")?;
+    let mut import = PostmanImport {
+        name: doc["info"]["name"]
+            .as_str()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or("Postman")
+            .to_string(),
+        ..PostmanImport::default()
+    };
+    collect_items(items, "", doc.get("auth"), &mut import.requests)?;
+    if let Some(variables) = doc.get("variable").and_then(Value::as_array) {
+        import.variables = variables
+            .iter()
+            .filter(|v| v["disabled"] != json!(true))
+            .filter_map(|v| Some((v["key"].as_str()?.to_string(), scalar(&v["value"]))))
+            .collect();
+    }
+    Ok(import)
+}
+
+fn collect_items(
+    items: &[Value],
+    prefix: &str,
+    inherited_auth: Option<&Value>,
+    out: &mut Vec<SavedRequest>,
+) -> Result<(), String> {
+    for item in items {
+        let name = item["name"].as_str().unwrap_or_default();
+        let label = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix} / {name}")
+        };
+        let auth = item.get("auth").or(inherited_auth);
+        if let Some(children) = item.get("item").and_then(Value::as_array) {
+            collect_items(children, &label, auth, out)?;
+        } else if let Some(request) = item.get("request") {
+            let request = from_postman_request(request, auth)
+                .map_err(|e| format!("Request '{label}': {e}"))?;
+            out.push(SavedRequest::new(label, request));
+        }
+    }
+    Ok(())
+}
+
+fn from_postman_request(
+    request: &Value,
+    inherited_auth: Option<&Value>,
+) -> Result<HttpRequest, String> {
+    // A request may be given as just its URL.
+    if let Some(url) = request.as_str() {
+        return Ok(HttpRequest {
+            method: Some(HttpMethod::GET),
+            url: url.to_string(),
+            ..HttpRequest::default()
+        });
+    }
+    let method = request["method"]
+        .as_str()
+        .unwrap_or("GET")
+        .parse::<HttpMethod>()?;
+    let (url, names) = from_postman_path(&postman_url(&request["url"]));
+    let values: Vec<(String, String)> = enabled(&request["url"]["variable"])
+        .filter_map(|v| Some((v["key"].as_str()?.to_string(), scalar(&v["value"]))))
+        .collect();
+    let mut out = HttpRequest {
+        method: Some(method),
+        url,
+        path_template: !names.is_empty(),
+        path_vars: names
+            .into_iter()
+            .map(|name| {
+                let value = values
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default();
+                (name, value)
+            })
+            .collect(),
+        notes: request["description"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        ..HttpRequest::default()
+    };
+
+    let mut headers = HeaderMap::new();
+    for header in enabled(&request["header"]) {
+        let key = header["key"].as_str().unwrap_or_default().trim();
+        let (Ok(name), Ok(value)) = (
+            key.parse::<HeaderName>(),
+            scalar(&header["value"]).parse::<HeaderValue>(),
+        ) else {
+            continue;
+        };
+        headers.append(name, value);
+    }
+    // Keep the collection's own Content-Type rather than adding ours.
+    out.manual_content_type = headers.contains_key(CONTENT_TYPE);
+    out.headers = headers;
+
+    let body = &request["body"];
+    match body["mode"].as_str() {
+        Some("raw") => {
+            out.body = body["raw"].as_str().map(str::to_string);
+        }
+        Some("urlencoded") => {
+            out.body_source = BodySource::Form(FormEncoding::UrlEncoded);
+            out.form_fields = form_fields(&body["urlencoded"]);
+        }
+        Some("formdata") => {
+            out.body_source = BodySource::Form(FormEncoding::Multipart);
+            out.form_fields = form_fields(&body["formdata"]);
+        }
+        Some("file") => {
+            if let Some(src) = body["file"]["src"].as_str() {
+                out.body_source = BodySource::File(src.into());
+            }
+        }
+        Some("graphql") => {
+            out.body = serde_json::to_string_pretty(&json!({
+                "query": body["graphql"]["query"],
+                "variables": body["graphql"]["variables"]
+                    .as_str()
+                    .and_then(|v| serde_json::from_str::<Value>(v).ok())
+                    .unwrap_or(Value::Null),
+            }))
+            .ok();
+        }
+        _ => {}
+    }
+
+    if let Some(auth) = request.get("auth").or(inherited_auth) {
+        match auth["type"].as_str() {
+            Some("basic") => {
+                out.auth = Auth::Basic;
+                out.username = auth_param(&auth["basic"], "username");
+                out.password = auth_param(&auth["basic"], "password");
+            }
+            Some("bearer") => {
+                out.auth = Auth::Bearer;
+                out.token = auth_param(&auth["bearer"], "token");
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// The URL as typed in Postman: either a string or an object whose `raw`
+/// field holds it (older exports only have `host`/`path` parts).
+fn postman_url(url: &Value) -> String {
+    if let Some(url) = url.as_str() {
+        return url.to_string();
+    }
+    if let Some(raw) = url["raw"].as_str() {
+        return raw.to_string();
+    }
+    let join = |parts: &Value, sep: &str| match parts {
+        Value::Array(parts) => parts.iter().map(scalar).collect::<Vec<_>>().join(sep),
+        other => scalar(other),
+    };
+    let mut out = String::new();
+    if let Some(protocol) = url["protocol"].as_str() {
+        out.push_str(&format!("{protocol}://"));
+    }
+    out.push_str(&join(&url["host"], "."));
+    if let Some(port) = url["port"].as_str() {
+        out.push_str(&format!(":{port}"));
+    }
+    let path = join(&url["path"], "/");
+    if !path.is_empty() {
+        out.push('/');
+        out.push_str(path.trim_start_matches('/'));
+    }
+    let query: Vec<String> = enabled(&url["query"])
+        .map(|q| format!("{}={}", scalar(&q["key"]), scalar(&q["value"])))
+        .collect();
+    if !query.is_empty() {
+        out.push('?');
+        out.push_str(&query.join("&"));
+    }
+    out
+}
+
+/// Rewrites Postman's `:name` path segments as our `{name}` placeholders.
+/// Returns the new URL and the variable names, in order.
+fn from_postman_path(url: &str) -> (String, Vec<String>) {
+    let (path, tail) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let mut names = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name)
+                if !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '-') =>
+            {
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+                format!("{{{name}}}")
+            }
+            _ => segment.to_string(),
+        })
+        .collect();
+    (segments.join("/") + tail, names)
+}
+
+fn form_fields(fields: &Value) -> Vec<FormField> {
+    enabled(fields)
+        .map(|field| {
+            let is_file = field["type"].as_str() == Some("file");
+            FormField {
+                key: scalar(&field["key"]),
+                // Postman allows several files per field; we take the first.
+                value: if is_file {
+                    match &field["src"] {
+                        Value::Array(srcs) => srcs.first().map(scalar).unwrap_or_default(),
+                        src => scalar(src),
+                    }
+                } else {
+                    scalar(&field["value"])
+                },
+                kind: if is_file {
+                    FormFieldKind::File
+                } else {
+                    FormFieldKind::Text
+                },
+            }
+        })
+        .collect()
+}
+
+/// Reads `key` from an auth block: a list of `{key, value}` pairs in
+/// v2.1, a plain object in v2.0.
+fn auth_param(params: &Value, key: &str) -> String {
+    match params {
+        Value::Array(pairs) => pairs
+            .iter()
+            .find(|pair| pair["key"].as_str() == Some(key))
+            .map(|pair| scalar(&pair["value"]))
+            .unwrap_or_default(),
+        other => scalar(&other[key]),
+    }
+}
+
+/// Entries of a Postman list that aren't switched off.
+fn enabled(list: &Value) -> impl Iterator<Item = &Value> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry["disabled"] != json!(true))
+}
+
+/// A string field, or the JSON text of any other value (numbers and
+/// booleans show up in variables).
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// A `key`/`value` pair as Postman writes them in auth and form lists.
+fn kv(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": value, "type": "string" })
+}
+
+fn postman_request(request: &HttpRequest) -> Value {
+    let headers: Vec<Value> = request
+        .header_pairs()
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": value }))
+        .collect();
+    let mut out = json!({
+        "method": request
+            .method
+            .as_ref()
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| "GET".to_string()),
+        "header": headers,
+        "url": postman_request_url(request),
+        "auth": match request.auth {
+            Auth::None => json!({ "type": "noauth" }),
+            Auth::Basic => json!({
+                "type": "basic",
+                "basic": [kv("username", &request.username), kv("password", &request.password)],
+            }),
+            Auth::Bearer => json!({
+                "type": "bearer",
+                "bearer": [kv("token", &request.token)],
+            }),
+        },
+    });
+    if !request.notes.is_empty() {
+        out["description"] = json!(request.notes);
+    }
+    if let Some(body) = postman_body(request) {
+        out["body"] = body;
+    }
+    out
+}
+
+/// The URL of `request`; with a path template its `{name}` segments become
+/// Postman's `:name` with the values in `url.variable`. Placeholders inside
+/// a segment (`v{version}`) have no Postman form and are left as typed.
+fn postman_request_url(request: &HttpRequest) -> Value {
+    if !request.path_template {
+        return json!(request.url);
+    }
+    let url = &request.url;
+    let (path, tail) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let mut variables = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| {
+            let name = segment
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .filter(|name| !name.is_empty() && !name.contains(['{', '}']));
+            match name {
+                Some(name) => {
+                    let value = request
+                        .path_vars
+                        .iter()
+                        .find(|(key, _)| key == name)
+                        .map(|(_, value)| value.as_str())
+                        .unwrap_or_default();
+                    if !variables.iter().any(|v: &Value| v["key"] == name) {
+                        variables.push(json!({ "key": name, "value": value }));
+                    }
+                    format!(":{name}")
+                }
+                None => segment.to_string(),
+            }
+        })
+        .collect();
+    json!({
+        "raw": segments.join("/") + tail,
+        "variable": variables,
+    })
+}
+
+fn postman_body(request: &HttpRequest) -> Option<Value> {
+    let fields = || request.form_fields.iter().filter(|f| !f.key.is_empty());
+    Some(match &request.body_source {
+        BodySource::File(path) => json!({ "mode": "file", "file": { "src": path } }),
+        BodySource::Form(FormEncoding::UrlEncoded) => json!({
+            "mode": "urlencoded",
+            "urlencoded": fields()
+                .filter(|f| f.kind == FormFieldKind::Text)
+                .map(|f| json!({ "key": f.key, "value": f.value, "type": "text" }))
+                .collect::<Vec<_>>(),
+        }),
+        BodySource::Form(FormEncoding::Multipart) => json!({
+            "mode": "formdata",
+            "formdata": fields()
+                .map(|f| match f.kind {
+                    FormFieldKind::Text => json!({ "key": f.key, "value": f.value, "type": "text" }),
+                    FormFieldKind::File => json!({ "key": f.key, "src": f.value, "type": "file" }),
+                })
+                .collect::<Vec<_>>(),
+        }),
+        BodySource::Inline => {
+            let body = request.body.as_deref().filter(|b| !b.trim().is_empty())?;
+            let language = if serde_json::from_str::<Value>(body).is_ok() {
+                "json"
+            } else {
+                "text"
+            };
+            json!({
+                "mode": "raw",
+                "raw": body,
+                "options": { "raw": { "language": language } },
+            })
+        }
+    })
+}
@@ -0,0 +1,102 @@
+use crate::env;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Built-in functions a pre-request step can apply to its input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transform {
+    #[default]
+    Base64,
+    Hex,
+    Sha256,
+    HmacSha256,
+    HmacSha256Base64,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 5] = [
+        Transform::Base64,
+        Transform::Hex,
+        Transform::Sha256,
+        Transform::HmacSha256,
+        Transform::HmacSha256Base64,
+    ];
+
+    pub fn needs_key(self) -> bool {
+        matches!(self, Transform::HmacSha256 | Transform::HmacSha256Base64)
+    }
+
+    fn apply(self, input: &[u8], key: &[u8]) -> String {
+        match self {
+            Transform::Base64 => base64::engine::general_purpose::STANDARD.encode(input),
+            Transform::Hex => to_hex(input),
+            Transform::Sha256 => to_hex(&Sha256::digest(input)),
+            Transform::HmacSha256 => to_hex(&hmac_sha256(key, input)),
+            Transform::HmacSha256Base64 => {
+                base64::engine::general_purpose::STANDARD.encode(hmac_sha256(key, input))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Transform::Base64 => "base64",
+            Transform::Hex => "hex",
+            Transform::Sha256 => "sha256 (hex)",
+            Transform::HmacSha256 => "hmac-sha256 (hex)",
+            Transform::HmacSha256Base64 => "hmac-sha256 (base64)",
+        })
+    }
+}
+
+/// Computes `variable` from `input` before the request is sent, e.g. an
+/// HMAC signature of `{{body}}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreRequestStep {
+    pub variable: String,
+    pub input: String,
+    pub transform: Transform,
+    /// Secret for the HMAC transforms; may reference variables.
+    pub key: String,
+}
+
+/// Runs `steps` in order and returns the variables they define. Inputs and
+/// keys see `vars`, `{{body}}` and the results of earlier steps.
+pub fn run_steps(
+    steps: &[PreRequestStep],
+    vars: &[(String, String)],
+    body: &str,
+) -> Vec<(String, String)> {
+    let mut scope: Vec<(String, String)> = vars.to_vec();
+    scope.push(("body".to_string(), body.to_string()));
+    let mut defined = Vec::new();
+    for step in steps {
+        let name = step.variable.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let input = env::substitute(&step.input, &scope);
+        let key = env::substitute(&step.key, &scope);
+        let value = step.transform.apply(input.as_bytes(), key.as_bytes());
+        // Later definitions shadow earlier ones, as `substitute` takes the
+        // first match.
+        scope.insert(0, (name.to_string(), value.clone()));
+        defined.insert(0, (name.to_string(), value));
+    }
+    defined
+}
+
+fn hmac_sha256(key: &[u8], input: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(input);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
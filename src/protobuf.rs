@@ -0,0 +1,32 @@
+use prost_reflect::{DescriptorPool, DynamicMessage};
+use serde_json::Value;
+use std::path::Path;
+
+/// Reads a `FileDescriptorSet`, as written by
+/// `protoc --include_imports --descriptor_set_out=...`.
+pub fn load_descriptor(path: &Path) -> Result<DescriptorPool, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    DescriptorPool::decode(bytes.as_slice()).map_err(|e| format!("Not a FileDescriptorSet: {e}"))
+}
+
+/// Fully qualified names of every message type in the pool, sorted.
+pub fn message_names(pool: &DescriptorPool) -> Vec<String> {
+    let mut names: Vec<String> = pool
+        .all_messages()
+        .map(|message| message.full_name().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Decodes `bytes` as message `name` and converts it to JSON using the
+/// canonical protobuf JSON mapping.
+pub fn decode_to_json(pool: &DescriptorPool, name: &str, bytes: &[u8]) -> Result<Value, String> {
+    let descriptor = pool
+        .get_message_by_name(name)
+        .ok_or_else(|| format!("Unknown message type {name}"))?;
+    let message = DynamicMessage::decode(descriptor, bytes)
+        .map_err(|e| format!("Could not decode the response as {name}: {e}"))?;
+    serde_json::to_value(&message).map_err(|e| e.to_string())
+}
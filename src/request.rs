@@ -1,23 +1,47 @@
-use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
-use reqwest::{Error, Response};
+use crate::env;
+use crate::prerequest::PreRequestStep;
+use iced::Color;
+use iced::futures::{Stream, stream};
+use reqwest::header::{
+    ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, COOKIE, HeaderMap, HeaderName,
+    HeaderValue, PROXY_AUTHORIZATION, SET_COOKIE,
+};
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HttpMethod {
     GET,
     POST,
     PUT,
     PATCH,
     DELETE,
+    Custom(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Auth {
     None,
     Basic,
     Bearer,
 }
 
+impl std::fmt::Display for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Auth::None => "None",
+            Auth::Basic => "Basic",
+            Auth::Bearer => "Bearer",
+        })
+    }
+}
+
 impl Auth {
+    pub const ALL: [Auth; 3] = [Auth::None, Auth::Basic, Auth::Bearer];
+
     pub fn to_int(&self) -> Option<u8> {
         match self {
             Auth::None => Some(0),
@@ -41,12 +65,115 @@ impl Default for Auth {
     }
 }
 
+impl HttpMethod {
+    /// The methods offered in the method picker.
+    pub const STANDARD: [HttpMethod; 5] = [
+        HttpMethod::GET,
+        HttpMethod::POST,
+        HttpMethod::PUT,
+        HttpMethod::PATCH,
+        HttpMethod::DELETE,
+    ];
+
+    /// Color used wherever the method is shown, following the usual API
+    /// client conventions.
+    pub fn color(&self) -> Color {
+        match self {
+            HttpMethod::GET => Color::from_rgb8(80, 250, 123),
+            HttpMethod::POST => Color::from_rgb8(255, 184, 108),
+            HttpMethod::PUT | HttpMethod::PATCH => Color::from_rgb8(241, 250, 140),
+            HttpMethod::DELETE => Color::from_rgb8(255, 100, 100),
+            HttpMethod::Custom(_) => Color::from_rgb8(189, 147, 249),
+        }
+    }
+}
+
 impl Default for HttpMethod {
     fn default() -> Self {
         HttpMethod::GET
     }
 }
 
+impl std::str::FromStr for HttpMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "GET" => Ok(HttpMethod::GET),
+            "POST" => Ok(HttpMethod::POST),
+            "PUT" => Ok(HttpMethod::PUT),
+            "PATCH" => Ok(HttpMethod::PATCH),
+            "DELETE" => Ok(HttpMethod::DELETE),
+            _ if is_valid_method_token(s) => Ok(HttpMethod::Custom(s.to_string())),
+            _ => Err(format!("Invalid HTTP method '{s}'")),
+        }
+    }
+}
+
+// Methods are stored as their plain name ("GET", "PURGE", ...).
+impl Serialize for HttpMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpMethod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl HttpMethod {
+    fn to_reqwest(&self) -> Result<reqwest::Method, SendError> {
+        let name = self.to_string();
+        if !is_valid_method_token(&name) {
+            return Err(SendError::InvalidMethod(name));
+        }
+        reqwest::Method::from_bytes(name.as_bytes()).map_err(|_| SendError::InvalidMethod(name))
+    }
+}
+
+/// A method must be a non-empty RFC 9110 token: no spaces, separators or
+/// control characters.
+pub fn is_valid_method_token(method: &str) -> bool {
+    !method.is_empty()
+        && method
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendError {
+    #[error("No HTTP method selected")]
+    NoMethod,
+    #[error("Invalid HTTP method '{0}': only letters, digits and !#$%&'*+-.^_`|~ are allowed")]
+    InvalidMethod(String),
+    #[error("Could not open body file {path}: {source}")]
+    BodyFile {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("Could not create the HTTP client: {0}")]
+    ClientBuild(String),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl SendError {
+    /// Message shown in the response area when a request fails.
+    pub fn describe(&self) -> String {
+        match self {
+            SendError::Http(e) => classify_error(e),
+            SendError::ClientBuild(_) => {
+                format!("❌ {self}\n💡 Check the proxy and CA certificate options in Settings.")
+            }
+            other => format!("❌ {other}"),
+        }
+    }
+}
+
 impl ToString for HttpMethod {
     fn to_string(&self) -> String {
         match self {
@@ -55,16 +182,1085 @@ impl ToString for HttpMethod {
             HttpMethod::PUT => "PUT",
             HttpMethod::PATCH => "PATCH",
             HttpMethod::DELETE => "DELETE",
+            HttpMethod::Custom(method) => method,
         }
         .to_string()
     }
 }
 
+/// Turns a `reqwest::Error` into a short, human readable explanation with a
+/// hint on how to fix it. The raw error is kept at the end for reference.
+pub fn classify_error(e: &reqwest::Error) -> String {
+    let mut chain = String::new();
+    let mut source = std::error::Error::source(e);
+    while let Some(err) = source {
+        chain.push_str(&err.to_string().to_lowercase());
+        chain.push('\n');
+        source = err.source();
+    }
+
+    let (summary, hint) = if e.is_builder() {
+        (
+            "Invalid request",
+            "Check that the URL is complete, including the scheme (http:// or https://).",
+        )
+    } else if e.is_timeout() {
+        (
+            "The request timed out",
+            "The server took too long to answer. Check that it is running and reachable.",
+        )
+    } else if chain.contains("dns error") || chain.contains("failed to lookup address") {
+        (
+            "DNS lookup failed",
+            "The host name could not be resolved. Check the URL for typos or your network/VPN.",
+        )
+    } else if chain.contains("certificate") || chain.contains("tls") || chain.contains("ssl") {
+        (
+            "TLS error",
+            "The secure connection could not be established. Check the certificate or try http://.",
+        )
+    } else if chain.contains("connection refused") {
+        (
+            "Connection refused",
+            "Nothing is listening on that host/port. Check that the server is running and the port is right.",
+        )
+    } else if e.is_connect() {
+        (
+            "Could not connect",
+            "The server could not be reached. Check the host, port and your network connection.",
+        )
+    } else if e.is_redirect() {
+        // Our redirect policy explains when the limit was hit.
+        if let Some(limit) = std::error::Error::source(e)
+            .map(|err| err.to_string())
+            .filter(|err| err.starts_with("too many redirects"))
+        {
+            return format!(
+                "❌ {limit}\n💡 The server probably redirects in a loop. Raise the limit in Settings if the chain is expected.\n\nDetails: {e}"
+            );
+        }
+        (
+            "Redirect error",
+            "The server redirected too many times or to an invalid location.",
+        )
+    } else if e.is_body() || e.is_decode() {
+        (
+            "Failed to read the response",
+            "The connection was interrupted or the response body was malformed.",
+        )
+    } else if e.is_request() {
+        (
+            "The request could not be sent",
+            "The connection was closed unexpectedly. Try again.",
+        )
+    } else {
+        ("Request failed", "See the details below.")
+    };
+
+    format!("❌ {summary}\n💡 {hint}\n\nDetails: {e}")
+}
+
+/// Where the request body comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BodySource {
+    /// The text typed in the body editor (`HttpRequest::body`).
+    #[default]
+    Inline,
+    /// A file streamed from disk at send time, sent as-is.
+    File(PathBuf),
+    /// The fields in `HttpRequest::form_fields`.
+    Form(FormEncoding),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FormEncoding {
+    /// `application/x-www-form-urlencoded`
+    #[default]
+    UrlEncoded,
+    /// `multipart/form-data`
+    Multipart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FormFieldKind {
+    #[default]
+    Text,
+    /// `value` is a path whose content is uploaded (multipart only).
+    File,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormField {
+    pub key: String,
+    pub value: String,
+    pub kind: FormFieldKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BodyFormat {
+    #[default]
+    AsTyped,
+    Pretty,
+    Minified,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LineEnding {
+    #[default]
+    AsTyped,
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub const ALL: [LineEnding; 3] = [LineEnding::AsTyped, LineEnding::Lf, LineEnding::CrLf];
+
+    pub fn apply(&self, body: String) -> String {
+        match self {
+            LineEnding::AsTyped => body,
+            LineEnding::Lf => body.replace("\r\n", "\n"),
+            LineEnding::CrLf => body.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineEnding::AsTyped => write!(f, "As typed"),
+            LineEnding::Lf => write!(f, "LF (\\n)"),
+            LineEnding::CrLf => write!(f, "CRLF (\\r\\n)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BodyContentType {
+    Json,
+    Xml,
+    Form,
+    Text,
+}
+
+impl BodyContentType {
+    pub fn mime(&self) -> &'static str {
+        match self {
+            BodyContentType::Json => "application/json",
+            BodyContentType::Xml => "application/xml",
+            BodyContentType::Form => "application/x-www-form-urlencoded",
+            BodyContentType::Text => "text/plain",
+        }
+    }
+
+    /// Sniffs the body: `<` is XML, `{`/`[` is JSON, `key=value&...` is a
+    /// form and anything else is plain text. Blank bodies have no type.
+    pub fn detect(body: &str) -> Option<Self> {
+        let body = body.trim();
+        match body.chars().next()? {
+            '<' => Some(BodyContentType::Xml),
+            '{' | '[' => Some(BodyContentType::Json),
+            _ if looks_form_encoded(body) => Some(BodyContentType::Form),
+            _ => Some(BodyContentType::Text),
+        }
+    }
+
+    /// Guesses the type of a body file from its extension. Other files
+    /// (images, archives, ...) get no type rather than a wrong one.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(BodyContentType::Json),
+            "xml" => Some(BodyContentType::Xml),
+            "txt" | "csv" | "log" | "md" => Some(BodyContentType::Text),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for BodyContentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            BodyContentType::Json => "JSON",
+            BodyContentType::Xml => "XML",
+            BodyContentType::Form => "Form",
+            BodyContentType::Text => "Text",
+        };
+        write!(f, "{label}")
+    }
+}
+
+fn looks_form_encoded(body: &str) -> bool {
+    body.split('&').all(|pair| {
+        pair.split_once('=')
+            .is_some_and(|(key, _)| !key.is_empty() && !key.contains(char::is_whitespace))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContentTypeMode {
+    #[default]
+    Auto,
+    Fixed(BodyContentType),
+}
+
+impl ContentTypeMode {
+    pub const ALL: [ContentTypeMode; 5] = [
+        ContentTypeMode::Auto,
+        ContentTypeMode::Fixed(BodyContentType::Json),
+        ContentTypeMode::Fixed(BodyContentType::Xml),
+        ContentTypeMode::Fixed(BodyContentType::Form),
+        ContentTypeMode::Fixed(BodyContentType::Text),
+    ];
+}
+
+impl std::fmt::Display for ContentTypeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContentTypeMode::Auto => write!(f, "Auto-detect"),
+            ContentTypeMode::Fixed(t) => write!(f, "{t}"),
+        }
+    }
+}
+
+/// Re-serializes `url` with its path and query properly percent-encoded.
+/// Sequences that are already encoded (like `%20`) are left untouched.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    url::Url::parse(url.trim())
+        .map(|u| u.to_string())
+        .map_err(|e| format!("Invalid URL '{url}': {e}"))
+}
+
+/// The URL as it goes on the wire when `url` contains non-ASCII characters:
+/// international domain names become Punycode (`xn--...`) and the rest is
+/// percent-encoded. `None` for plain ASCII or unparsable URLs.
+pub fn encoded_url_preview(url: &str) -> Option<String> {
+    if url.is_ascii() {
+        return None;
+    }
+    normalize_url(url).ok()
+}
+
+/// Path part of `url`: everything before the query string or fragment.
+fn url_path_part(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// Names of the `{name}` placeholders in the path of `url`, in order and
+/// without duplicates. `{{var}}` environment tokens are not placeholders.
+pub fn path_variable_names(url: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = url_path_part(url);
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        if let Some(inner) = after.strip_prefix('{') {
+            // Skip the whole `{{var}}` token.
+            rest = inner.find("}}").map_or("", |end| &inner[end + 2..]);
+            continue;
+        }
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 1..];
+    }
+    names
+}
+
+/// Replaces the `{name}` placeholders in the path of `url` with the values
+/// in `vars`, percent-encoded as path segments. Empty and unknown
+/// placeholders are left as typed.
+pub fn substitute_path_variables(url: &str, vars: &[(String, String)]) -> String {
+    let path_len = url_path_part(url).len();
+    let (path, tail) = url.split_at(path_len);
+    let mut path = path.to_string();
+    for (name, value) in vars.iter().filter(|(_, value)| !value.is_empty()) {
+        path = path.replace(&format!("{{{name}}}"), &encode_path_segment(value));
+    }
+    path + tail
+}
+
+/// Percent-encodes `value` so it stays a single path segment (`/`, `?`,
+/// `#`, `%`, spaces and non-ASCII are escaped). `{{var}}` tokens are kept
+/// for the environment substitution that runs afterwards.
+fn encode_path_segment(value: &str) -> String {
+    fn escape(out: &mut String, text: &str) {
+        for byte in text.bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&byte) {
+                out.push(byte as char);
+            } else {
+                out.push_str(&format!("%{byte:02X}"));
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        escape(&mut out, &rest[..start]);
+        out.push_str(&rest[start..start + len + 2]);
+        rest = &rest[start + len + 2..];
+    }
+    escape(&mut out, rest);
+    out
+}
+
+/// Replaces the host and/or port of `url` with `authority`, which may be
+/// `host`, `host:port` or just `:port`.
+pub fn override_authority(url: &str, authority: &str) -> Result<String, String> {
+    let mut parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL '{url}': {e}"))?;
+    let authority = authority.trim();
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => {
+            let port = port
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid port '{port}' in host override"))?;
+            (host, Some(port))
+        }
+        _ => (authority, None),
+    };
+    if !host.is_empty() {
+        parsed
+            .set_host(Some(host))
+            .map_err(|e| format!("Invalid host '{host}': {e}"))?;
+    }
+    if let Some(port) = port {
+        parsed
+            .set_port(Some(port))
+            .map_err(|_| format!("Cannot set a port on '{url}'"))?;
+    }
+    Ok(parsed.to_string())
+}
+
+/// Parses a raw HTTP request: request line, headers, a blank line and the
+/// body. A relative request target is resolved against the `Host` header.
+pub fn parse_raw_http(text: &str) -> Result<HttpRequest, String> {
+    let mut lines = text.lines().skip_while(|l| l.trim().is_empty());
+    let request_line = lines.next().ok_or("The raw request is empty")?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts
+        .next()
+        .ok_or("Missing method in request line")?
+        .parse::<HttpMethod>()?;
+    let target = parts.next().ok_or("Missing URL in request line")?;
+
+    let mut headers = Vec::new();
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("Malformed header line: {line}"))?;
+        headers.push((key.trim().to_string(), value.trim().to_string()));
+    }
+    let body = lines.collect::<Vec<_>>().join("\n");
+
+    let url = if target.starts_with("http://") || target.starts_with("https://") {
+        target.to_string()
+    } else {
+        let host = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("host"))
+            .map(|(_, v)| v)
+            .ok_or("A relative request target needs a Host header")?;
+        format!("http://{host}{target}")
+    };
+
+    let mut request = HttpRequest {
+        method: Some(method),
+        url,
+        body: (!body.is_empty()).then_some(body),
+        ..Default::default()
+    };
+    request.set_headers(&headers);
+    Ok(request)
+}
+
+/// Parses `Key: Value` lines as copied from browser devtools. Blank lines,
+/// HTTP/2 pseudo-headers (`:authority`) and lines that are not a valid
+/// header are skipped.
+pub fn parse_header_block(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once(':')?;
+            let key = key.trim();
+            key.parse::<HeaderName>().ok()?;
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Headers that carry credentials and must not be shared or stored as-is.
+pub fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == AUTHORIZATION.as_str()
+        || name == PROXY_AUTHORIZATION.as_str()
+        || name == COOKIE.as_str()
+        || name == SET_COOKIE.as_str()
+        || name.contains("api-key")
+        || name.contains("token")
+        || name.contains("secret")
+}
+
+/// Why a header row cannot be sent as typed. Rows with an empty name are
+/// ignored and never an error.
+pub fn header_problem(key: &str, value: &str) -> Option<String> {
+    if key.trim().is_empty() {
+        return None;
+    }
+    if key.trim().parse::<HeaderName>().is_err() {
+        return Some(format!("'{key}' is not a valid header name"));
+    }
+    if value.contains(['\r', '\n']) {
+        return Some(format!("The value of '{key}' contains line breaks"));
+    }
+    if value.chars().any(|c| c.is_control() && c != '\t') {
+        return Some(format!("The value of '{key}' contains control characters"));
+    }
+    if value.parse::<HeaderValue>().is_err() {
+        return Some(format!("The value of '{key}' contains invalid characters"));
+    }
+    None
+}
+
+/// Non-blocking hint for values with stray whitespace, which usually comes
+/// from pasting and makes tokens silently mismatch.
+pub fn header_whitespace_hint(value: &str) -> Option<&'static str> {
+    if value.trim() != value {
+        Some("Leading or trailing whitespace")
+    } else if value.contains('\t') {
+        Some("Contains a tab")
+    } else {
+        None
+    }
+}
+
+/// Joins a value that was wrapped over several lines, such as a base64
+/// token copied from a terminal. Only offered when every line looks like
+/// base64, so unrelated text is not silently glued together.
+pub fn join_wrapped_value(value: &str) -> Option<String> {
+    let joined: String = value.lines().map(str::trim).collect();
+    let base64_like = !joined.is_empty()
+        && joined
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+/=-_.".contains(c));
+    base64_like.then_some(joined)
+}
+
+/// Rate-limit state advertised by the server in the response headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Seconds until the window resets.
+    pub reset_in: Option<u64>,
+}
+
+impl std::fmt::Display for RateLimitInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rate limit: ")?;
+        match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) => write!(f, "{remaining}/{limit}")?,
+            (Some(remaining), None) => write!(f, "{remaining} remaining")?,
+            (None, Some(limit)) => write!(f, "limit {limit}")?,
+            (None, None) => write!(f, "exceeded")?,
+        }
+        if let Some(reset_in) = self.reset_in {
+            write!(f, ", resets in {reset_in}s")?;
+        }
+        Ok(())
+    }
+}
+
+/// The delay asked for by a `Retry-After` header, given either in seconds
+/// or as an HTTP date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("retry-after")?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+/// Reads the `X-RateLimit-*` family of headers (and the `X-Rate-Limit-*` and
+/// IETF `RateLimit-*` spellings). `Retry-After` is used as the reset time
+/// when nothing else provides one.
+pub fn parse_rate_limit(headers: &HeaderMap) -> Option<RateLimitInfo> {
+    let find = |suffixes: &[&str]| {
+        ["x-ratelimit-", "x-rate-limit-", "ratelimit-"]
+            .iter()
+            .flat_map(|prefix| {
+                suffixes
+                    .iter()
+                    .map(move |suffix| format!("{prefix}{suffix}"))
+            })
+            .find_map(|name| headers.get(name.as_str()))
+            .and_then(|v| v.to_str().ok())
+            // `RateLimit-Limit: 100, 100;w=60` style values: keep the first number.
+            .and_then(|v| v.split([',', ';']).next())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    let limit = find(&["limit"]);
+    let remaining = find(&["remaining"]);
+    let reset = find(&["reset", "reset-after"]).or_else(|| {
+        headers
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    });
+    if limit.is_none() && remaining.is_none() && reset.is_none() {
+        return None;
+    }
+
+    // Some APIs send an absolute Unix timestamp instead of a delay.
+    let reset_in = reset.map(|reset| {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        if reset > 1_000_000_000 {
+            reset.saturating_sub(now)
+        } else {
+            reset
+        }
+    });
+
+    Some(RateLimitInfo {
+        limit,
+        remaining,
+        reset_in,
+    })
+}
+
+/// One `Set-Cookie` header split into its parts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieInfo {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    pub expires: Option<String>,
+    /// Seconds; takes precedence over `expires` (RFC 6265).
+    pub max_age: Option<i64>,
+    pub http_only: bool,
+    pub secure: bool,
+    pub same_site: Option<String>,
+}
+
+impl CookieInfo {
+    /// When the cookie goes away, as shown in the cookie table.
+    pub fn expiry(&self) -> String {
+        match (self.max_age, &self.expires) {
+            (Some(secs), _) if secs <= 0 => "Deleted".to_string(),
+            (Some(secs), _) => format!("in {secs}s"),
+            (None, Some(expires)) => expires.clone(),
+            (None, None) => "Session".to_string(),
+        }
+    }
+
+    pub fn flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.http_only {
+            flags.push("HttpOnly".to_string());
+        }
+        if self.secure {
+            flags.push("Secure".to_string());
+        }
+        if let Some(same_site) = &self.same_site {
+            flags.push(format!("SameSite={same_site}"));
+        }
+        flags.join(" ")
+    }
+}
+
+/// Parses a `Set-Cookie` value (`name=value; Path=/; HttpOnly`). Attribute
+/// names are case-insensitive; unknown attributes are ignored. `None` when
+/// there is no `name=value` pair (browsers ignore such headers).
+pub fn parse_set_cookie(value: &str) -> Option<CookieInfo> {
+    let mut parts = value.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    if name.trim().is_empty() {
+        return None;
+    }
+    let mut cookie = CookieInfo {
+        name: name.trim().to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        ..CookieInfo::default()
+    };
+    for attribute in parts {
+        let (key, value) = match attribute.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+            None => (attribute.trim(), None),
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "domain" => cookie.domain = value,
+            "path" => cookie.path = value,
+            "expires" => cookie.expires = value,
+            "max-age" => cookie.max_age = value.and_then(|v| v.parse().ok()),
+            "httponly" => cookie.http_only = true,
+            "secure" => cookie.secure = true,
+            "samesite" => cookie.same_site = value,
+            _ => {}
+        }
+    }
+    Some(cookie)
+}
+
+/// Every `Set-Cookie` of a response; servers send one header per cookie.
+pub fn set_cookies(headers: &HeaderMap) -> Vec<CookieInfo> {
+    headers
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(parse_set_cookie)
+        .collect()
+}
+
+/// Short plain-language explanation of an HTTP status code.
+pub fn describe_status(code: u16) -> &'static str {
+    match code {
+        100 => "The server received the headers; the client may send the body.",
+        101 => "The server is switching protocols as requested (e.g. to WebSocket).",
+        200 => "The request succeeded.",
+        201 => "The request succeeded and a new resource was created.",
+        202 => "The request was accepted but has not been processed yet.",
+        204 => "The request succeeded and there is no content to return.",
+        206 => "Only part of the resource was returned (range request).",
+        301 => "The resource moved permanently; use the new URL from now on.",
+        302 => "The resource is temporarily at another URL.",
+        303 => "See the other URL (usually with a GET) for the result.",
+        304 => "Not modified since the cached version; nothing was sent again.",
+        307 => "Temporary redirect; repeat the same request at the new URL.",
+        308 => "Permanent redirect; repeat the same request at the new URL.",
+        400 => "The server could not understand the request (malformed syntax or body).",
+        401 => "Authentication is missing or invalid. Check the Auth tab.",
+        403 => "You are authenticated but not allowed to access this resource.",
+        404 => "Nothing was found at this URL.",
+        405 => "The HTTP method is not allowed for this URL.",
+        406 => "The server cannot produce a response matching the Accept headers.",
+        408 => "The server timed out waiting for the request.",
+        409 => "The request conflicts with the current state of the resource.",
+        410 => "The resource is gone and will not come back.",
+        411 => "The server requires a Content-Length header.",
+        412 => "A precondition header (If-Match, If-Unmodified-Since...) failed.",
+        413 => "The request body is too large.",
+        414 => "The URL is too long.",
+        415 => "The body's Content-Type is not supported by the server.",
+        418 => "I'm a teapot: an April Fools' joke code (RFC 2324).",
+        422 => "The body is well-formed but failed validation.",
+        423 => "The resource is locked.",
+        425 => "The server refuses to risk processing a replayed request.",
+        426 => "The client must switch to a different protocol.",
+        428 => "The server requires the request to be conditional.",
+        429 => "Too many requests: you are being rate limited.",
+        431 => "The request headers are too large.",
+        451 => "Unavailable for legal reasons.",
+        500 => "The server hit an unexpected error.",
+        501 => "The server does not support this functionality.",
+        502 => "A gateway or proxy got an invalid response from the upstream server.",
+        503 => "The server is overloaded or down for maintenance.",
+        504 => "A gateway or proxy timed out waiting for the upstream server.",
+        _ => match code / 100 {
+            1 => "Informational response.",
+            2 => "Success.",
+            3 => "Redirection: the resource is elsewhere.",
+            4 => "Client error: something is wrong with the request.",
+            5 => "Server error: the server failed to fulfil a valid request.",
+            _ => "Non-standard status code.",
+        },
+    }
+}
+
+/// Undoes a (possibly chained, e.g. `gzip, br`) Content-Encoding.
+fn decompress(codings: &str, raw: &[u8]) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let mut data = raw.to_vec();
+    // Codings are listed in the order they were applied.
+    for coding in codings.split(',').map(str::trim).rev() {
+        let mut out = Vec::new();
+        let result = match coding {
+            "gzip" | "x-gzip" => flate2::read::GzDecoder::new(&data[..]).read_to_end(&mut out),
+            "deflate" => flate2::read::ZlibDecoder::new(&data[..])
+                .read_to_end(&mut out)
+                .or_else(|_| {
+                    // Some servers send raw deflate without the zlib wrapper.
+                    out.clear();
+                    flate2::read::DeflateDecoder::new(&data[..]).read_to_end(&mut out)
+                }),
+            "br" => brotli::Decompressor::new(&data[..], 4096).read_to_end(&mut out),
+            "identity" => continue,
+            other => return Err(format!("unsupported encoding '{other}'")),
+        };
+        result.map_err(|e| e.to_string())?;
+        data = out;
+    }
+    Ok(data)
+}
+
+/// Value of the Accept-Encoding header added to every request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AcceptEncoding {
+    /// Don't add the header.
+    #[default]
+    Unset,
+    Identity,
+    Gzip,
+    Brotli,
+    /// `gzip, deflate, br`
+    Any,
+}
+
+impl AcceptEncoding {
+    pub const ALL: [AcceptEncoding; 5] = [
+        AcceptEncoding::Unset,
+        AcceptEncoding::Identity,
+        AcceptEncoding::Gzip,
+        AcceptEncoding::Brotli,
+        AcceptEncoding::Any,
+    ];
+
+    fn header_value(&self) -> Option<&'static str> {
+        match self {
+            AcceptEncoding::Unset => None,
+            AcceptEncoding::Identity => Some("identity"),
+            AcceptEncoding::Gzip => Some("gzip"),
+            AcceptEncoding::Brotli => Some("br"),
+            AcceptEncoding::Any => Some("gzip, deflate, br"),
+        }
+    }
+}
+
+impl std::fmt::Display for AcceptEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.header_value() {
+            Some(value) => write!(f, "{value}"),
+            None => write!(f, "Not set"),
+        }
+    }
+}
+
+/// Connection options used to build the `reqwest::Client` for a send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientOptions {
+    /// Proxy URL for all traffic, e.g. `http://localhost:8080`. Empty means
+    /// none, or the system proxy when `use_system_proxy` is set.
+    pub proxy: String,
+    /// Honor `HTTP_PROXY` / `HTTPS_PROXY` / `NO_PROXY` (and the OS proxy
+    /// settings on macOS and Windows) when no proxy is entered.
+    pub use_system_proxy: bool,
+    /// Extra PEM root certificates to trust, e.g. a company CA.
+    pub ca_certs: Vec<PathBuf>,
+    pub accept_encoding: AcceptEncoding,
+    /// Seconds an idle pooled connection is kept open; 0 keeps it forever.
+    pub pool_idle_timeout_secs: u64,
+    /// Idle connections kept per host; 0 disables reuse.
+    pub pool_max_idle_per_host: usize,
+    /// When off, 3xx responses are shown instead of being followed.
+    pub follow_redirects: bool,
+    pub max_redirects: usize,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            proxy: String::new(),
+            use_system_proxy: true,
+            ca_certs: Vec::new(),
+            accept_encoding: AcceptEncoding::default(),
+            pool_idle_timeout_secs: 90,
+            pool_max_idle_per_host: 32,
+            follow_redirects: true,
+            max_redirects: 10,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Redirects followed by the request being sent on this task; the
+    /// client is shared, so the chain can't live in the redirect policy.
+    static REDIRECT_HOPS: RefCell<Vec<String>>;
+}
+
+/// The proxy a client built from `ClientOptions` goes through.
+#[derive(Debug, PartialEq, Eq)]
+enum ProxyMode<'a> {
+    Explicit(&'a str),
+    System,
+    Off,
+}
+
+impl ClientOptions {
+    fn proxy_mode(&self) -> ProxyMode<'_> {
+        let proxy = self.proxy.trim();
+        if !proxy.is_empty() {
+            ProxyMode::Explicit(proxy)
+        } else if self.use_system_proxy {
+            ProxyMode::System
+        } else {
+            ProxyMode::Off
+        }
+    }
+
+    pub fn build_client(&self) -> Result<reqwest::Client, SendError> {
+        let mut builder = reqwest::Client::builder().redirect(self.redirect_policy());
+        // reqwest picks up the system proxy by default; adding an explicit
+        // proxy replaces it, and `no_proxy` turns it off.
+        match self.proxy_mode() {
+            ProxyMode::Explicit(proxy) => {
+                let proxy = reqwest::Proxy::all(proxy)
+                    .map_err(|e| SendError::ClientBuild(format!("invalid proxy '{proxy}': {e}")))?;
+                builder = builder.proxy(proxy);
+            }
+            ProxyMode::System => {}
+            ProxyMode::Off => builder = builder.no_proxy(),
+        }
+        for path in &self.ca_certs {
+            builder = builder.add_root_certificate(load_ca_cert(path)?);
+        }
+        builder
+            .pool_idle_timeout(
+                (self.pool_idle_timeout_secs > 0)
+                    .then(|| Duration::from_secs(self.pool_idle_timeout_secs)),
+            )
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .build()
+            .map_err(|e| SendError::ClientBuild(e.to_string()))
+    }
+
+    fn redirect_policy(&self) -> reqwest::redirect::Policy {
+        if !self.follow_redirects {
+            return reqwest::redirect::Policy::none();
+        }
+        let max = self.max_redirects;
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > max {
+                let chain: Vec<&str> = attempt.previous().iter().map(url::Url::as_str).collect();
+                let error = format!("too many redirects (>{max}): {}", chain.join(" → "));
+                return attempt.error(error);
+            }
+            let _ =
+                REDIRECT_HOPS.try_with(|hops| hops.borrow_mut().push(attempt.url().to_string()));
+            attempt.follow()
+        })
+    }
+}
+
+/// A client built from `ClientOptions`, kept across sends so its connection
+/// pool (and the pool settings) actually apply. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    pub options: ClientOptions,
+    client: reqwest::Client,
+}
+
+impl HttpClient {
+    pub fn new(options: &ClientOptions) -> Result<Self, SendError> {
+        Ok(HttpClient {
+            options: options.clone(),
+            client: options.build_client()?,
+        })
+    }
+}
+
+/// Quotes `s` for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// `key=value` / `key:=json` items for a flat JSON object body, or `None`
+/// when the body has to be sent raw. `{}` is sent raw too: with no items
+/// httpie would send no body at all.
+fn httpie_json_items(body: &str) -> Option<Vec<String>> {
+    let serde_json::Value::Object(map) = serde_json::from_str(body).ok()? else {
+        return None;
+    };
+    if map.is_empty() {
+        return None;
+    }
+    Some(
+        map.iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(s) => shell_quote(&format!("{key}={s}")),
+                other => shell_quote(&format!("{key}:={other}")),
+            })
+            .collect(),
+    )
+}
+
+/// Reads a PEM certificate from disk.
+pub fn load_ca_cert(path: &Path) -> Result<reqwest::Certificate, SendError> {
+    let pem = std::fs::read(path).map_err(|e| {
+        SendError::ClientBuild(format!("could not read CA file {}: {e}", path.display()))
+    })?;
+    reqwest::Certificate::from_pem(&pem)
+        .map_err(|e| SendError::ClientBuild(format!("invalid PEM in {}: {e}", path.display())))
+}
+
+/// Progress of a request whose body is read chunk by chunk.
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    Started {
+        status: StatusCode,
+        headers: HeaderMap,
+        /// URLs of the redirects followed before this response.
+        redirects: Vec<String>,
+    },
+    Chunk(Vec<u8>),
+    Finished(Duration),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// `bytes` decoded with the charset from Content-Type (UTF-8 by default).
+    pub body: String,
+    /// The body after undoing any Content-Encoding.
+    pub bytes: Vec<u8>,
+    /// Size of the body on the wire, before decompression.
+    pub wire_size: usize,
+    /// The Content-Encoding used by the server, if any.
+    pub content_encoding: Option<String>,
+    pub elapsed: Duration,
+    /// URLs the client was redirected to, in order.
+    pub redirects: Vec<String>,
+}
+
+impl HttpResponse {
+    /// Builds the response from the body as received, decompressing it
+    /// according to Content-Encoding and decoding it with the charset.
+    pub fn new(status: StatusCode, headers: HeaderMap, raw: Vec<u8>, elapsed: Duration) -> Self {
+        let wire_size = raw.len();
+        let content_encoding = headers
+            .get(CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty() && v != "identity");
+        let (bytes, content_encoding) = match content_encoding {
+            Some(coding) => match decompress(&coding, &raw) {
+                Ok(bytes) => (bytes, Some(coding)),
+                Err(e) => (raw, Some(format!("{coding} (not decoded: {e})"))),
+            },
+            None => (raw, None),
+        };
+
+        let encoding = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| {
+                v.split(';')
+                    .filter_map(|param| param.trim().split_once('='))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("charset"))
+                    .map(|(_, charset)| charset.trim_matches('"').to_string())
+            })
+            .and_then(|charset| encoding_rs::Encoding::for_label(charset.as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+        let body = encoding.decode(&bytes).0.into_owned();
+        Self {
+            status,
+            headers,
+            body,
+            bytes,
+            wire_size,
+            content_encoding,
+            elapsed,
+            redirects: Vec::new(),
+        }
+    }
+
+    /// Headers as `Key: Value` lines, in the order they were received.
+    pub fn header_block(&self) -> String {
+        self.headers
+            .iter()
+            .map(|(k, v)| format!("{k}: {}", String::from_utf8_lossy(v.as_bytes())))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn has_json_content_type(&self) -> bool {
+        self.headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("json"))
+    }
+
+    pub fn has_form_content_type(&self) -> bool {
+        self.headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("application/x-www-form-urlencoded"))
+    }
+
+    /// The body decoded as `a=1&b=2` pairs, one `key = value` per line with
+    /// the keys aligned.
+    pub fn pretty_form_body(&self) -> String {
+        let pairs: Vec<(String, String)> = url::form_urlencoded::parse(self.body.trim().as_bytes())
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        let width = pairs
+            .iter()
+            .map(|(k, _)| k.chars().count())
+            .max()
+            .unwrap_or(0);
+        pairs
+            .iter()
+            .map(|(k, v)| format!("{k:width$} = {v}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// `HeaderMap` has no serde support; store it as a list of name/value pairs.
+mod header_map_serde {
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(headers: &HeaderMap, serializer: S) -> Result<S::Ok, S::Error> {
+        headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.to_str().unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HeaderMap, D::Error> {
+        let mut headers = HeaderMap::new();
+        for (key, value) in Vec::<(String, String)>::deserialize(deserializer)? {
+            if let (Ok(name), Ok(value)) = (key.parse::<HeaderName>(), value.parse::<HeaderValue>())
+            {
+                headers.append(name, value);
+            }
+        }
+        Ok(headers)
+    }
+}
+
 // Insert headers example:
 // data.headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 // data.headers.insert(USER_AGENT, HeaderValue::from_static("PatchLite/0.1"));
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HttpRequest {
     pub method: Option<HttpMethod>,
     pub url: String,
@@ -73,7 +1269,23 @@ pub struct HttpRequest {
     pub token: String,
     pub username: String,
     pub password: String,
+    #[serde(with = "header_map_serde")]
     pub headers: HeaderMap,
+    pub body_format: BodyFormat,
+    pub content_type: ContentTypeMode,
+    /// When set, no Content-Type is added; only the user's own header is sent.
+    pub manual_content_type: bool,
+    pub line_ending: LineEnding,
+    pub charset: String,
+    pub body_source: BodySource,
+    pub form_fields: Vec<FormField>,
+    /// Free-text description of what the request is for.
+    pub notes: String,
+    /// Variables computed right before sending, e.g. signatures.
+    pub pre_request: Vec<PreRequestStep>,
+    /// Treat `{name}` segments of the URL path as variables.
+    pub path_template: bool,
+    pub path_vars: Vec<(String, String)>,
 }
 
 impl HttpRequest {
@@ -82,6 +1294,13 @@ impl HttpRequest {
             .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
     }
 
+    pub fn header_pairs(&self) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or_default().to_string()))
+            .collect()
+    }
+
     pub fn set_headers(&mut self, headers_vec: &Vec<(String, String)>) {
         let mut header_map = HeaderMap::new();
         for (key, value) in headers_vec {
@@ -94,106 +1313,780 @@ impl HttpRequest {
         self.headers = header_map;
     }
 
-    pub async fn send(&self) -> Result<Response, Error> {
-        let api_client = reqwest::Client::new();
-        match self.method {
-            Some(m) => match m {
-                HttpMethod::GET => {
-                    let mut req = api_client
-                        .get(self.url.clone())
-                        .headers(self.headers.clone());
-
-                    req = match self.auth {
-                        Auth::None => req,
-                        Auth::Bearer => req.bearer_auth(self.token.clone()),
-                        Auth::Basic => {
-                            req.basic_auth(self.username.clone(), Some(self.password.clone()))
-                        }
-                    };
+    /// True when credentials would travel unencrypted: auth is configured
+    /// (or an Authorization header is set) and the URL is plain `http://`
+    /// to a host other than loopback.
+    pub fn sends_credentials_in_clear(&self) -> bool {
+        let has_credentials = self.auth != Auth::None || self.headers.contains_key(AUTHORIZATION);
+        let Ok(url) = url::Url::parse(self.url.trim()) else {
+            return false;
+        };
+        let loopback = match url.host() {
+            Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        };
+        has_credentials && url.scheme() == "http" && !loopback
+    }
 
-                    req.send().await
-                }
-                HttpMethod::POST => {
-                    let mut req = api_client
-                        .post(self.url.clone())
-                        .headers(self.headers.clone());
-
-                    req = match self.auth {
-                        Auth::None => req,
-                        Auth::Bearer => req.bearer_auth(self.token.clone()),
-                        Auth::Basic => {
-                            req.basic_auth(self.username.clone(), Some(self.password.clone()))
-                        }
-                    };
+    /// Substitutes `{{name}}` variables in the URL, headers, body and
+    /// credentials.
+    pub fn apply_variables(&mut self, vars: &[(String, String)]) {
+        if vars.is_empty() {
+            return;
+        }
+        self.url = env::substitute(&self.url, vars);
+        if let Some(body) = &self.body {
+            self.body = Some(env::substitute(body, vars));
+        }
+        for value in self.headers.values_mut() {
+            if let Ok(text) = value.to_str()
+                && let Ok(substituted) = env::substitute(text, vars).parse()
+            {
+                *value = substituted;
+            }
+        }
+        for field in &mut self.form_fields {
+            field.value = env::substitute(&field.value, vars);
+        }
+        self.token = env::substitute(&self.token, vars);
+        self.username = env::substitute(&self.username, vars);
+        self.password = env::substitute(&self.password, vars);
+    }
+
+    /// Path variables whose placeholder is in the URL but have no value.
+    pub fn unfilled_path_variables(&self) -> Vec<String> {
+        if !self.path_template {
+            return Vec::new();
+        }
+        path_variable_names(&self.url)
+            .into_iter()
+            .filter(|name| {
+                !self
+                    .path_vars
+                    .iter()
+                    .any(|(key, value)| key == name && !value.is_empty())
+            })
+            .collect()
+    }
+
+    /// Applies the path variables and `vars`, then the variables computed by
+    /// the pre-request steps (which sign the body as it will be sent).
+    pub fn resolve_variables(&mut self, vars: &[(String, String)]) {
+        if self.path_template {
+            self.url = substitute_path_variables(&self.url, &self.path_vars);
+        }
+        self.apply_variables(vars);
+        let computed =
+            crate::prerequest::run_steps(&self.pre_request, vars, &self.body_for_signing());
+        self.apply_variables(&computed);
+    }
+
+    // What `{{body}}` stands for in the pre-request steps: the bytes `send`
+    // puts on the wire. A multipart body gets a random boundary per send, so
+    // there is nothing stable to sign and it is left empty.
+    fn body_for_signing(&self) -> String {
+        match &self.body_source {
+            BodySource::Inline => self.body_to_send().unwrap_or_default(),
+            BodySource::File(path) => std::fs::read(path)
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .unwrap_or_default(),
+            BodySource::Form(FormEncoding::UrlEncoded) => {
+                url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(self.url_encoded_fields())
+                    .finish()
+            }
+            BodySource::Form(FormEncoding::Multipart) => String::new(),
+        }
+    }
+
+    /// Adds `globals` to the headers; headers already set on this request win.
+    /// Pairs that are not valid header names/values are skipped.
+    pub fn merge_global_headers(&mut self, globals: &[(String, String)]) {
+        for (key, value) in globals {
+            let (Ok(name), Ok(value)) = (key.trim().parse::<HeaderName>(), value.parse()) else {
+                continue;
+            };
+            if !self.headers.contains_key(&name) {
+                self.headers.insert(name, value);
+            }
+        }
+    }
+
+    /// Content type the body will be sent with, if there is a body at all.
+    pub fn effective_content_type(&self) -> Option<BodyContentType> {
+        // The editor text is not what gets sent for a file body.
+        if let BodySource::File(path) = &self.body_source {
+            return match self.content_type {
+                ContentTypeMode::Auto => BodyContentType::from_path(path),
+                ContentTypeMode::Fixed(t) => Some(t),
+            };
+        }
+        let body = self.body.as_deref().unwrap_or("");
+        if body.trim().is_empty() {
+            return None;
+        }
+        match self.content_type {
+            ContentTypeMode::Auto => BodyContentType::detect(body),
+            ContentTypeMode::Fixed(t) => Some(t),
+        }
+    }
+
+    pub fn apply_content_type(&mut self) {
+        // Form bodies get their Content-Type (and multipart boundary) from reqwest.
+        if self.manual_content_type || matches!(self.body_source, BodySource::Form(_)) {
+            return;
+        }
+        // Auto only fills in a missing header; one the user typed is kept.
+        if self.content_type == ContentTypeMode::Auto && self.headers.contains_key(CONTENT_TYPE) {
+            return;
+        }
+        if let Some(t) = self.effective_content_type() {
+            self.headers
+                .insert(CONTENT_TYPE, HeaderValue::from_static(t.mime()));
+        }
+
+        let charset = self.charset.trim();
+        if charset.is_empty() {
+            return;
+        }
+        let declared = self
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.contains("charset="))
+            .map(|v| format!("{v}; charset={charset}"));
+        if let Some(value) = declared.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            self.headers.insert(CONTENT_TYPE, value);
+        }
+    }
+
+    fn has_json_content_type(&self) -> bool {
+        self.headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("json"))
+    }
+
+    // Reformats the body according to `body_format` (only JSON bodies that
+    // parse are touched) and then normalizes its line endings.
+    fn body_to_send(&self) -> Option<String> {
+        let body = self.body.as_ref().filter(|b| !b.is_empty())?;
+        let formatted = if self.body_format == BodyFormat::AsTyped || !self.has_json_content_type()
+        {
+            body.clone()
+        } else {
+            match serde_json::from_str::<serde_json::Value>(body) {
+                Ok(value) => match self.body_format {
+                    BodyFormat::Pretty => serde_json::to_string_pretty(&value).ok()?,
+                    _ => serde_json::to_string(&value).ok()?,
+                },
+                Err(_) => body.clone(),
+            }
+        };
+        Some(self.line_ending.apply(formatted))
+    }
 
-                    if let Some(body) = self.body.as_ref().filter(|b| !b.trim().is_empty()) {
-                        if serde_json::from_str::<serde_json::Value>(body).is_ok() {
-                            req = req.body(body.clone());
+    /// Renders the request as an HTTPie command line.
+    pub fn to_httpie(&self) -> String {
+        let program = if self.url.trim_start().starts_with("https") {
+            "https"
+        } else {
+            "http"
+        };
+        let mut args = vec![program.to_string()];
+
+        match self.auth {
+            Auth::None => {}
+            Auth::Basic => {
+                args.push("-a".to_string());
+                args.push(shell_quote(&format!("{}:{}", self.username, self.password)));
+            }
+            Auth::Bearer => {
+                args.push("-A bearer -a".to_string());
+                args.push(shell_quote(&self.token));
+            }
+        }
+
+        // Body items and flags that must come before the URL.
+        let mut items = Vec::new();
+        match &self.body_source {
+            BodySource::File(path) => items.push(shell_quote(&format!("@{}", path.display()))),
+            BodySource::Form(encoding) => {
+                args.push(match encoding {
+                    FormEncoding::UrlEncoded => "--form".to_string(),
+                    FormEncoding::Multipart => "--multipart".to_string(),
+                });
+                for field in self.form_fields.iter().filter(|f| !f.key.is_empty()) {
+                    items.push(shell_quote(&match field.kind {
+                        FormFieldKind::Text => format!("{}={}", field.key, field.value),
+                        FormFieldKind::File => format!("{}@{}", field.key, field.value),
+                    }));
+                }
+            }
+            BodySource::Inline => {
+                if let Some(body) = self.body.as_deref().filter(|b| !b.trim().is_empty()) {
+                    match httpie_json_items(body) {
+                        Some(fields) => items.extend(fields),
+                        None => {
+                            args.push("--raw".to_string());
+                            args.push(shell_quote(body));
                         }
                     }
+                }
+            }
+        }
+
+        if let Some(method) = &self.method {
+            args.push(method.to_string());
+        }
+        args.push(shell_quote(&self.url));
+        for (name, value) in &self.headers {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            args.push(shell_quote(&format!("{name}:{value}")));
+        }
+        args.extend(items);
+        args.join(" ")
+    }
+
+    /// Renders the request as a curl command line, with the body exactly as
+    /// `dispatch` would send it.
+    pub fn to_curl(&self) -> String {
+        let mut args = vec!["curl".to_string()];
+        if let Some(method) = &self.method {
+            args.push(format!("-X {}", shell_quote(&method.to_string())));
+        }
+        args.push(shell_quote(&self.url));
 
-                    req.send().await
+        match self.auth {
+            Auth::None => {}
+            Auth::Basic => {
+                args.push("-u".to_string());
+                args.push(shell_quote(&format!("{}:{}", self.username, self.password)));
+            }
+            Auth::Bearer => {
+                args.push("-H".to_string());
+                args.push(shell_quote(&format!(
+                    "Authorization: Bearer {}",
+                    self.token
+                )));
+            }
+        }
+        for (name, value) in &self.headers {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            args.push("-H".to_string());
+            args.push(shell_quote(&format!("{name}: {value}")));
+        }
+
+        match &self.body_source {
+            BodySource::File(path) => {
+                args.push("--data-binary".to_string());
+                args.push(shell_quote(&format!("@{}", path.display())));
+            }
+            BodySource::Form(FormEncoding::UrlEncoded) => {
+                for (key, value) in self.url_encoded_fields() {
+                    args.push("--data-urlencode".to_string());
+                    args.push(shell_quote(&format!("{key}={value}")));
                 }
-                HttpMethod::PUT => {
-                    let mut req = api_client
-                        .put(self.url.clone())
-                        .headers(self.headers.clone());
-
-                    req = match self.auth {
-                        Auth::None => req,
-                        Auth::Bearer => req.bearer_auth(self.token.clone()),
-                        Auth::Basic => {
-                            req.basic_auth(self.username.clone(), Some(self.password.clone()))
-                        }
-                    };
+            }
+            BodySource::Form(FormEncoding::Multipart) => {
+                for field in self.form_fields.iter().filter(|f| !f.key.is_empty()) {
+                    args.push("-F".to_string());
+                    args.push(shell_quote(&match field.kind {
+                        FormFieldKind::Text => format!("{}={}", field.key, field.value),
+                        FormFieldKind::File => format!("{}=@{}", field.key, field.value),
+                    }));
+                }
+            }
+            BodySource::Inline => {
+                if let Some(body) = self.body_to_send() {
+                    args.push("--data-raw".to_string());
+                    args.push(shell_quote(&body));
+                }
+            }
+        }
+        args.join(" ")
+    }
 
-                    if self.body.is_some() && !self.body.as_ref().unwrap().is_empty() {
-                        req = req.body(self.body.as_ref().unwrap().clone());
-                    }
+    // File rows have no meaning in a URL-encoded form and are skipped.
+    fn url_encoded_fields(&self) -> Vec<(&str, &str)> {
+        self.form_fields
+            .iter()
+            .filter(|f| !f.key.is_empty() && f.kind == FormFieldKind::Text)
+            .map(|f| (f.key.as_str(), f.value.as_str()))
+            .collect()
+    }
 
-                    req.send().await
+    async fn multipart_form(&self) -> Result<reqwest::multipart::Form, SendError> {
+        let mut form = reqwest::multipart::Form::new();
+        for field in self.form_fields.iter().filter(|f| !f.key.is_empty()) {
+            form = match field.kind {
+                FormFieldKind::Text => form.text(field.key.clone(), field.value.clone()),
+                FormFieldKind::File => {
+                    let path = Path::new(&field.value);
+                    let bytes =
+                        tokio::fs::read(path)
+                            .await
+                            .map_err(|source| SendError::BodyFile {
+                                path: field.value.clone(),
+                                source,
+                            })?;
+                    let mut part = reqwest::multipart::Part::bytes(bytes);
+                    if let Some(name) = path.file_name() {
+                        part = part.file_name(name.to_string_lossy().into_owned());
+                    }
+                    form.part(field.key.clone(), part)
                 }
-                HttpMethod::PATCH => {
-                    let mut req = api_client
-                        .patch(self.url.clone())
-                        .headers(self.headers.clone());
-
-                    req = match self.auth {
-                        Auth::None => req,
-                        Auth::Bearer => req.bearer_auth(self.token.clone()),
-                        Auth::Basic => req.basic_auth("admin", Some("good password")),
-                    };
-
-                    if self.body.is_some() && !self.body.as_ref().unwrap().is_empty() {
-                        req = req.body(self.body.as_ref().unwrap().clone());
+            };
+        }
+        Ok(form)
+    }
+
+    pub async fn send(&self, client: &HttpClient) -> Result<HttpResponse, SendError> {
+        let started = Instant::now();
+        let (response, redirects) = self.dispatch(client).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await?.to_vec();
+        Ok(HttpResponse {
+            redirects,
+            ..HttpResponse::new(status, headers, bytes, started.elapsed())
+        })
+    }
+
+    /// Like `send`, but yields the body as it arrives instead of buffering it.
+    pub fn send_streaming(self, client: HttpClient) -> impl Stream<Item = StreamEvent> {
+        enum State {
+            Start(Box<HttpRequest>, HttpClient),
+            Reading(Response, Instant),
+            Done,
+        }
+
+        stream::unfold(State::Start(Box::new(self), client), |state| async move {
+            match state {
+                State::Start(request, client) => {
+                    let started = Instant::now();
+                    match request.dispatch(&client).await {
+                        Ok((response, redirects)) => {
+                            let event = StreamEvent::Started {
+                                status: response.status(),
+                                headers: response.headers().clone(),
+                                redirects,
+                            };
+                            Some((event, State::Reading(response, started)))
+                        }
+                        Err(e) => Some((StreamEvent::Failed(e.describe()), State::Done)),
                     }
+                }
+                State::Reading(mut response, started) => match response.chunk().await {
+                    Ok(Some(bytes)) => Some((
+                        StreamEvent::Chunk(bytes.to_vec()),
+                        State::Reading(response, started),
+                    )),
+                    Ok(None) => Some((StreamEvent::Finished(started.elapsed()), State::Done)),
+                    Err(e) => Some((
+                        StreamEvent::Failed(SendError::from(e).describe()),
+                        State::Done,
+                    )),
+                },
+                State::Done => None,
+            }
+        })
+    }
+
+    /// Sends the request; also returns the URLs of any redirects followed.
+    async fn dispatch(&self, client: &HttpClient) -> Result<(Response, Vec<String>), SendError> {
+        let options = &client.options;
+        let method = self
+            .method
+            .as_ref()
+            .ok_or(SendError::NoMethod)?
+            .to_reqwest()?;
+
+        let mut headers = self.headers.clone();
+        // reqwest sets the form Content-Type, including the multipart boundary.
+        if matches!(self.body_source, BodySource::Form(_)) {
+            headers.remove(CONTENT_TYPE);
+        }
+        if let Some(value) = options.accept_encoding.header_value()
+            && !headers.contains_key(ACCEPT_ENCODING)
+        {
+            headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(value));
+        }
+        // An explicit Host header is sent as-is: hyper only derives Host
+        // from the URL when the header is missing. Over HTTP/2 servers go by
+        // the `:authority` pseudo-header, which always follows the URL.
+        let mut req = client
+            .client
+            .request(method.clone(), self.url.clone())
+            .headers(headers);
+
+        req = match self.auth {
+            Auth::None => req,
+            Auth::Bearer => req.bearer_auth(self.token.clone()),
+            Auth::Basic => req.basic_auth(self.username.clone(), Some(self.password.clone())),
+        };
+
+        if let BodySource::File(path) = &self.body_source {
+            let file = tokio::fs::File::open(path)
+                .await
+                .map_err(|source| SendError::BodyFile {
+                    path: path.display().to_string(),
+                    source,
+                })?;
+            req = req.body(reqwest::Body::from(file));
+        } else if let BodySource::Form(encoding) = self.body_source {
+            req = match encoding {
+                FormEncoding::UrlEncoded => req.form(&self.url_encoded_fields()),
+                FormEncoding::Multipart => req.multipart(self.multipart_form().await?),
+            };
+        } else if method != reqwest::Method::GET
+            && let Some(body) = self.body_to_send()
+        {
+            // POST only sends JSON bodies that actually parse.
+            if method != reqwest::Method::POST
+                || !self.has_json_content_type()
+                || serde_json::from_str::<serde_json::Value>(&body).is_ok()
+            {
+                req = req.body(body);
+            }
+        }
 
-                    req.send().await
+        REDIRECT_HOPS
+            .scope(RefCell::new(Vec::new()), async move {
+                let response = req.send().await?;
+                let redirects = REDIRECT_HOPS.with(|hops| hops.take());
+                Ok::<_, SendError>((response, redirects))
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HOST;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn invalid_proxy_is_a_friendly_client_error() {
+        let options = ClientOptions {
+            proxy: "http://bad host:8080".to_string(),
+            ..ClientOptions::default()
+        };
+        let error = HttpClient::new(&options).unwrap_err();
+        assert!(matches!(error, SendError::ClientBuild(_)));
+        let message = error.describe();
+        assert!(
+            message.contains("invalid proxy 'http://bad host:8080'"),
+            "{message}"
+        );
+        assert!(message.contains("Check the proxy"), "{message}");
+    }
+
+    #[test]
+    fn system_proxy_toggle() {
+        let mut options = ClientOptions::default();
+        assert_eq!(options.proxy_mode(), ProxyMode::System);
+
+        options.use_system_proxy = false;
+        assert_eq!(options.proxy_mode(), ProxyMode::Off);
+        assert!(HttpClient::new(&options).is_ok());
+
+        // An explicit proxy wins either way.
+        options.proxy = " http://localhost:8080 ".to_string();
+        assert_eq!(
+            options.proxy_mode(),
+            ProxyMode::Explicit("http://localhost:8080")
+        );
+        options.use_system_proxy = true;
+        assert_eq!(
+            options.proxy_mode(),
+            ProxyMode::Explicit("http://localhost:8080")
+        );
+    }
+
+    #[test]
+    fn custom_host_header_reaches_the_wire() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut head = Vec::new();
+            let mut buf = [0; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
                 }
-                HttpMethod::DELETE => {
-                    let mut req = api_client
-                        .delete(self.url.clone())
-                        .headers(self.headers.clone());
-
-                    req = match self.auth {
-                        Auth::None => req,
-                        Auth::Bearer => req.bearer_auth(self.token.clone()),
-                        Auth::Basic => {
-                            req.basic_auth(self.username.clone(), Some(self.password.clone()))
-                        }
-                    };
+                head.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&head).to_lowercase()
+        });
 
-                    if self.body.is_some() && !self.body.as_ref().unwrap().is_empty() {
-                        req = req.body(self.body.as_ref().unwrap().clone());
-                    }
+        let mut req = HttpRequest {
+            method: Some(HttpMethod::GET),
+            url,
+            ..HttpRequest::default()
+        };
+        req.headers
+            .insert(HOST, HeaderValue::from_static("virtual.test"));
+        let client = HttpClient::new(&ClientOptions {
+            use_system_proxy: false,
+            ..ClientOptions::default()
+        })
+        .unwrap();
+        let response = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(req.send(&client))
+            .unwrap();
+        assert_eq!(response.status, StatusCode::NO_CONTENT);
 
-                    req.send().await
+        let head = server.join().unwrap();
+        assert!(head.contains("\r\nhost: virtual.test\r\n"), "{head}");
+        assert_eq!(head.matches("\r\nhost:").count(), 1, "{head}");
+    }
+
+    // Sends a signed JSON body reformatted as `body_format` with CRLF line
+    // endings; returns the X-Sig header and the body the server received.
+    fn send_signed_body(body_format: BodyFormat) -> (String, Vec<u8>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = Vec::new();
+            let mut buf = [0; 1024];
+            let head_end = loop {
+                if let Some(i) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break i + 4;
                 }
-            },
-            None => {
-                let result = reqwest::get("http://url_invalida###").await;
-                result
+                let n = stream.read(&mut buf).unwrap();
+                assert_ne!(n, 0, "connection closed before the headers ended");
+                data.extend_from_slice(&buf[..n]);
+            };
+            let head = String::from_utf8_lossy(&data[..head_end]).to_lowercase();
+            let length: usize = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length: "))
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap();
+            while data.len() < head_end + length {
+                let n = stream.read(&mut buf).unwrap();
+                assert_ne!(n, 0, "connection closed before the body ended");
+                data.extend_from_slice(&buf[..n]);
             }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            let signature = head
+                .lines()
+                .find_map(|l| l.strip_prefix("x-sig: "))
+                .unwrap()
+                .trim()
+                .to_string();
+            (signature, data[head_end..head_end + length].to_vec())
+        });
+
+        let mut req = HttpRequest {
+            method: Some(HttpMethod::PUT),
+            url,
+            body: Some("{\n  \"id\": \"{{id}}\",\n  \"tags\": [1, 2]\n}".to_string()),
+            body_format,
+            line_ending: LineEnding::CrLf,
+            pre_request: vec![PreRequestStep {
+                variable: "sig".to_string(),
+                input: "{{body}}".to_string(),
+                transform: crate::prerequest::Transform::HmacSha256,
+                key: "k".to_string(),
+            }],
+            ..HttpRequest::default()
+        };
+        req.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        req.headers
+            .insert("x-sig", HeaderValue::from_static("{{sig}}"));
+        req.resolve_variables(&[("id".to_string(), "42".to_string())]);
+
+        let client = HttpClient::new(&ClientOptions {
+            use_system_proxy: false,
+            ..ClientOptions::default()
+        })
+        .unwrap();
+        let response = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(req.send(&client))
+            .unwrap();
+        assert_eq!(response.status, StatusCode::NO_CONTENT);
+        server.join().unwrap()
+    }
+
+    #[test]
+    fn pre_request_signature_covers_the_sent_body() {
+        use hmac::{Hmac, Mac};
+
+        let expected_bodies: [(BodyFormat, &[u8]); 2] = [
+            (BodyFormat::Minified, br#"{"id":"42","tags":[1,2]}"#),
+            (
+                BodyFormat::Pretty,
+                b"{\r\n  \"id\": \"42\",\r\n  \"tags\": [\r\n    1,\r\n    2\r\n  ]\r\n}",
+            ),
+        ];
+        for (body_format, expected_body) in expected_bodies {
+            let (signature, body) = send_signed_body(body_format);
+            assert_eq!(body, expected_body, "{body_format:?}");
+            let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"k").unwrap();
+            mac.update(&body);
+            let expected: String = mac
+                .finalize()
+                .into_bytes()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            assert_eq!(signature, expected, "{body_format:?}");
         }
     }
+
+    #[test]
+    fn path_variables_are_encoded_as_segments() {
+        let vars = vec![
+            ("id".to_string(), "a/b c?é".to_string()),
+            ("org".to_string(), "{{orgId}}-x%".to_string()),
+        ];
+        assert_eq!(
+            substitute_path_variables("http://api.test/{org}/items/{id}?q={id}", &vars),
+            "http://api.test/{{orgId}}-x%25/items/a%2Fb%20c%3F%C3%A9?q={id}"
+        );
+    }
+
+    #[test]
+    fn set_cookie_attributes_are_case_insensitive() {
+        let cookie = parse_set_cookie(
+            r#"sid="abc"; PATH=/app; domain=example.com; HTTPONLY; secure; SameSite=Lax"#,
+        )
+        .unwrap();
+        assert_eq!(cookie.name, "sid");
+        assert_eq!(cookie.value, "abc");
+        assert_eq!(cookie.path.as_deref(), Some("/app"));
+        assert_eq!(cookie.domain.as_deref(), Some("example.com"));
+        assert!(cookie.http_only && cookie.secure);
+        assert_eq!(cookie.flags(), "HttpOnly Secure SameSite=Lax");
+    }
+
+    #[test]
+    fn set_cookie_max_age_wins_over_expires() {
+        let cookie =
+            parse_set_cookie("a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT; MAX-AGE=60").unwrap();
+        assert_eq!(cookie.max_age, Some(60));
+        assert_eq!(cookie.expiry(), "in 60s");
+
+        let cookie =
+            parse_set_cookie("a=; Max-Age=0; Expires=Wed, 21 Oct 2026 07:28:00 GMT").unwrap();
+        assert_eq!(cookie.expiry(), "Deleted");
+
+        let cookie = parse_set_cookie("a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT").unwrap();
+        assert_eq!(cookie.expiry(), "Wed, 21 Oct 2026 07:28:00 GMT");
+        assert_eq!(parse_set_cookie("a=1").unwrap().expiry(), "Session");
+    }
+
+    #[test]
+    fn set_cookies_skip_values_without_a_pair() {
+        assert_eq!(parse_set_cookie("garbage; Path=/"), None);
+        assert_eq!(parse_set_cookie("=x"), None);
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("garbage"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("b=2; Path=/"));
+        let cookies = set_cookies(&headers);
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].name, "b");
+    }
+
+    fn request(url: &str, body: &str) -> HttpRequest {
+        HttpRequest {
+            method: Some(HttpMethod::POST),
+            url: url.to_string(),
+            body: Some(body.to_string()),
+            ..HttpRequest::default()
+        }
+    }
+
+    #[test]
+    fn httpie_auth() {
+        let mut req = request("https://api.test/items", "");
+        assert_eq!(req.to_httpie(), "https POST https://api.test/items");
+
+        req.auth = Auth::Basic;
+        req.username = "ana".to_string();
+        req.password = "p w".to_string();
+        assert_eq!(
+            req.to_httpie(),
+            "https -a 'ana:p w' POST https://api.test/items"
+        );
+
+        req.auth = Auth::Bearer;
+        req.token = "abc.def".to_string();
+        assert_eq!(
+            req.to_httpie(),
+            "https -A bearer -a abc.def POST https://api.test/items"
+        );
+    }
+
+    #[test]
+    fn httpie_inline_bodies() {
+        let req = request("http://api.test", r#"{"name":"x y","n":1}"#);
+        assert_eq!(req.to_httpie(), "http POST http://api.test n:=1 'name=x y'");
+
+        let req = request("http://api.test", "{}");
+        assert_eq!(req.to_httpie(), "http --raw '{}' POST http://api.test");
+
+        let req = request("http://api.test", "[1, 2]");
+        assert_eq!(req.to_httpie(), "http --raw '[1, 2]' POST http://api.test");
+
+        let req = request("http://api.test", "plain text");
+        assert_eq!(
+            req.to_httpie(),
+            "http --raw 'plain text' POST http://api.test"
+        );
+    }
+
+    #[test]
+    fn httpie_file_and_form_bodies() {
+        let mut req = request("http://api.test", "");
+        req.body_source = BodySource::File(PathBuf::from("data.bin"));
+        assert_eq!(req.to_httpie(), "http POST http://api.test @data.bin");
+
+        req.form_fields = vec![
+            FormField {
+                key: "a".to_string(),
+                value: "1".to_string(),
+                kind: FormFieldKind::Text,
+            },
+            FormField {
+                key: "f".to_string(),
+                value: "x.png".to_string(),
+                kind: FormFieldKind::File,
+            },
+        ];
+        req.body_source = BodySource::Form(FormEncoding::UrlEncoded);
+        assert_eq!(
+            req.to_httpie(),
+            "http --form POST http://api.test a=1 f@x.png"
+        );
+
+        req.body_source = BodySource::Form(FormEncoding::Multipart);
+        assert_eq!(
+            req.to_httpie(),
+            "http --multipart POST http://api.test a=1 f@x.png"
+        );
+    }
 }
@@ -0,0 +1,25 @@
+use serde_json::Value;
+
+/// One place where a document does not conform to a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// JSON pointer of the offending value; empty for the root.
+    pub path: String,
+    pub message: String,
+}
+
+/// Validates `value` against the JSON Schema in `schema_src`. Fails when the
+/// schema itself is not valid JSON or not a valid schema.
+pub fn validate(schema_src: &str, value: &Value) -> Result<Vec<SchemaError>, String> {
+    let schema: Value =
+        serde_json::from_str(schema_src).map_err(|e| format!("The schema is not JSON: {e}"))?;
+    let validator =
+        jsonschema::validator_for(&schema).map_err(|e| format!("Invalid schema: {e}"))?;
+    Ok(validator
+        .iter_errors(value)
+        .map(|error| SchemaError {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect())
+}
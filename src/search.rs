@@ -0,0 +1,71 @@
+use regex::RegexBuilder;
+
+/// Matches beyond this are not listed.
+pub const MAX_MATCHES: usize = 500;
+
+/// One hit in the searched text, located by line so the editor can jump to it.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    /// Zero-based line number.
+    pub line: usize,
+    pub line_text: String,
+    /// Byte range of the match within `line_text`.
+    pub start: usize,
+    pub end: usize,
+    /// Capture groups of a regex match (`None` when a group did not take part).
+    pub groups: Vec<Option<String>>,
+}
+
+impl SearchMatch {
+    /// Character column of the match start, for cursor movement.
+    pub fn column(&self) -> usize {
+        self.line_text[..self.start].chars().count()
+    }
+
+    pub fn char_len(&self) -> usize {
+        self.line_text[self.start..self.end].chars().count()
+    }
+}
+
+/// Finds `query` in `text`, line by line. Plain queries are matched case
+/// insensitively; with `regex` the query is a case-sensitive pattern and an
+/// invalid one is reported as an error.
+pub fn find_matches(text: &str, query: &str, regex: bool) -> Result<Vec<SearchMatch>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = if regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let re = RegexBuilder::new(&pattern)
+        .case_insensitive(!regex)
+        .build()
+        .map_err(|e| format!("Invalid regex: {e}"))?;
+
+    let mut matches = Vec::new();
+    for (line, line_text) in text.lines().enumerate() {
+        for captures in re.captures_iter(line_text) {
+            let whole = captures.get(0).expect("group 0 always matches");
+            if whole.is_empty() {
+                continue;
+            }
+            matches.push(SearchMatch {
+                line,
+                line_text: line_text.to_string(),
+                start: whole.start(),
+                end: whole.end(),
+                groups: captures
+                    .iter()
+                    .skip(1)
+                    .map(|group| group.map(|g| g.as_str().to_string()))
+                    .collect(),
+            });
+            if matches.len() >= MAX_MATCHES {
+                return Ok(matches);
+            }
+        }
+    }
+    Ok(matches)
+}
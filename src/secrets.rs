@@ -0,0 +1,95 @@
+use crate::request::HttpRequest;
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+
+const SERVICE: &str = "patch-lite";
+
+/// The parts of a request that should never end up in a plain file.
+#[derive(Default, Serialize, Deserialize)]
+struct Secrets {
+    token: String,
+    password: String,
+}
+
+// Saved requests and the app's own values live in separate namespaces so
+// an id can never collide with an internal name.
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, name).map_err(|e| format!("Keychain unavailable: {e}"))
+}
+
+fn request_entry(id: &str) -> Result<Entry, String> {
+    entry(&format!("request:{id}"))
+}
+
+fn value_entry(name: &str) -> Result<Entry, String> {
+    entry(&format!("app:{name}"))
+}
+
+/// Stores the token and password of `request` in the keychain under the
+/// saved request's `id`.
+pub fn store(id: &str, request: &HttpRequest) -> Result<(), String> {
+    let secrets = Secrets {
+        token: request.token.clone(),
+        password: request.password.clone(),
+    };
+    if secrets.token.is_empty() && secrets.password.is_empty() {
+        remove(id);
+        return Ok(());
+    }
+    let json = serde_json::to_string(&secrets).map_err(|e| e.to_string())?;
+    request_entry(id)?
+        .set_password(&json)
+        .map_err(|e| format!("Keychain unavailable: {e}"))
+}
+
+/// Fills the token and password of `request` from the keychain. A missing
+/// entry is not an error: the request simply had no secrets.
+pub fn fetch(id: &str, request: &mut HttpRequest) -> Result<(), String> {
+    let json = match request_entry(id)?.get_password() {
+        Ok(json) => json,
+        Err(keyring::Error::NoEntry) => return Ok(()),
+        Err(e) => return Err(format!("Keychain unavailable: {e}")),
+    };
+    let secrets: Secrets = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    request.token = secrets.token;
+    request.password = secrets.password;
+    Ok(())
+}
+
+/// Stores a single secret value, such as an API token, under `name`.
+pub fn store_value(name: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        if let Ok(entry) = value_entry(name) {
+            let _ = entry.delete_credential();
+        }
+        return Ok(());
+    }
+    value_entry(name)?
+        .set_password(value)
+        .map_err(|e| format!("Keychain unavailable: {e}"))
+}
+
+/// Reads a value saved with `store_value`; empty when there is none.
+pub fn fetch_value(name: &str) -> Result<String, String> {
+    match value_entry(name)?.get_password() {
+        Ok(value) => Ok(value),
+        Err(keyring::Error::NoEntry) => Ok(String::new()),
+        Err(e) => Err(format!("Keychain unavailable: {e}")),
+    }
+}
+
+/// Deletes the secrets of the saved request `id`.
+pub fn remove(id: &str) {
+    if let Ok(entry) = request_entry(id) {
+        let _ = entry.delete_credential();
+    }
+}
+
+/// Copy of `request` without secrets, safe to write to disk.
+pub fn redacted(request: &HttpRequest) -> HttpRequest {
+    HttpRequest {
+        token: String::new(),
+        password: String::new(),
+        ..request.clone()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::json_highlight::{EditorFont, Indent};
+use crate::request::{Auth, ClientOptions, HttpMethod};
+use iced::Color;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// User preferences persisted next to the collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Keep auth secrets in the OS keychain instead of `collection.json`.
+    pub use_keychain: bool,
+    /// Append every request/response to `log_path` as NDJSON.
+    pub log_enabled: bool,
+    pub log_path: String,
+    /// Write credentials to the log instead of `[redacted]`.
+    pub log_auth: bool,
+    /// Offer "Share to Gist" in the response toolbar. The token itself is
+    /// kept in the keychain.
+    pub gist_enabled: bool,
+    /// Indentation used when pretty-printing JSON responses.
+    pub indent: Indent,
+    /// Hard-wrap the response text at this column; 0 disables it.
+    pub wrap_column: usize,
+    /// Font of the editors and the highlighted JSON.
+    pub editor_font: EditorFont,
+    /// Headers added to every request unless the request sets them itself.
+    pub global_headers: Vec<(String, String)>,
+    /// Responses faster than this (in ms) are shown in green.
+    pub fast_ms: u64,
+    /// Responses slower than this (in ms) are shown in red.
+    pub slow_ms: u64,
+    pub client: ClientOptions,
+    /// Requests the parallel runner keeps in flight at once.
+    pub max_concurrency: usize,
+    /// Pause between the requests of the sequential runner, in ms.
+    pub runner_delay_ms: u64,
+    /// Sent requests kept in the History tab; the oldest are dropped first.
+    pub max_history: usize,
+    /// Method and auth type of new (or cleared) requests.
+    pub default_method: HttpMethod,
+    pub default_auth: Auth,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            use_keychain: false,
+            log_enabled: false,
+            log_path: String::new(),
+            log_auth: false,
+            gist_enabled: false,
+            indent: Indent::default(),
+            wrap_column: 0,
+            editor_font: EditorFont::default(),
+            global_headers: Vec::new(),
+            fast_ms: 200,
+            slow_ms: 1000,
+            client: ClientOptions::default(),
+            max_concurrency: 8,
+            runner_delay_ms: 0,
+            max_history: 200,
+            default_method: HttpMethod::GET,
+            default_auth: Auth::None,
+        }
+    }
+}
+
+impl Settings {
+    /// Green, yellow or red depending on the configured thresholds.
+    pub fn timing_color(&self, elapsed: Duration) -> Color {
+        let ms = elapsed.as_millis();
+        if ms < u128::from(self.fast_ms) {
+            Color::from_rgb8(80, 250, 123)
+        } else if ms <= u128::from(self.slow_ms) {
+            Color::from_rgb8(241, 250, 140)
+        } else {
+            Color::from_rgb8(255, 100, 100)
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::request::HttpRequest;
+use crate::{gist, history};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+const SCHEME: &str = "patchlite://";
+
+/// Packs the request into a `patchlite://<base64 json>` link. Links get
+/// pasted in chats and tickets, so the token, password, credential headers
+/// and secrets in the URL are left out; the receiver fills in their own.
+pub fn encode_request(request: &HttpRequest) -> String {
+    let mut request = history::scrubbed(request);
+    request.url = gist::redact_url(&request.url);
+    let json = serde_json::to_vec(&request).unwrap_or_default();
+    format!("{SCHEME}{}", URL_SAFE_NO_PAD.encode(json))
+}
+
+/// Accepts either a full `patchlite://` link or just the base64 blob.
+pub fn decode_request(link: &str) -> Result<HttpRequest, String> {
+    let blob = link.trim();
+    let blob = blob.strip_prefix(SCHEME).unwrap_or(blob);
+    let bytes = URL_SAFE_NO_PAD
+        .decode(blob.trim_end_matches('='))
+        .map_err(|e| format!("Invalid share link: {e}"))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Invalid share link: {e}"))
+}
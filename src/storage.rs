@@ -0,0 +1,50 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// Directory where PatchLite keeps its files (e.g. `~/.config/patch-lite`).
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("patch-lite"))
+}
+
+/// Reads `file` from the data directory, or the default value when it does
+/// not exist yet. A file that can't be read or parsed is renamed to
+/// `<file>.bak`, so the next save doesn't overwrite it, and reported.
+pub fn load<T: DeserializeOwned + Default>(file: &str) -> Result<T, String> {
+    let Some(path) = data_dir().map(|dir| dir.join(file)) else {
+        return Ok(T::default());
+    };
+    let error = match std::fs::read_to_string(&path) {
+        Ok(json) => match serde_json::from_str(&json) {
+            Ok(value) => return Ok(value),
+            Err(e) => e.to_string(),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => e.to_string(),
+    };
+    let backup = path.with_file_name(format!("{file}.bak"));
+    Err(match std::fs::rename(&path, &backup) {
+        Ok(()) => format!(
+            "Could not load {file} ({error}); it was kept as {}",
+            backup.display()
+        ),
+        Err(_) => format!("Could not load {file}: {error}"),
+    })
+}
+
+/// Like `load`, but falls back to the default value and adds the error to
+/// `errors`.
+pub fn load_or_default<T: DeserializeOwned + Default>(file: &str, errors: &mut Vec<String>) -> T {
+    load(file).unwrap_or_else(|e| {
+        errors.push(e);
+        T::default()
+    })
+}
+
+pub fn save<T: Serialize>(file: &str, value: &T) -> Result<(), String> {
+    let dir = data_dir().ok_or("No configuration directory available")?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Could not create {}: {e}", dir.display()))?;
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(file), json).map_err(|e| format!("Could not save {file}: {e}"))
+}
@@ -0,0 +1,148 @@
+use crate::collection::{RunResult, RunSummary};
+use crate::settings::Settings;
+use iced::widget::canvas::{self, Frame, Geometry, Text};
+use iced::{Color, Element, Length, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse};
+
+const ROW_HEIGHT: f32 = 22.0;
+const BAR_HEIGHT: f32 = 14.0;
+const LABEL_WIDTH: f32 = 180.0;
+const AXIS_HEIGHT: f32 = 18.0;
+
+/// Gantt-style chart of a runner's results: one bar per request, from the
+/// moment it was sent until its response arrived.
+pub fn view<'a, Message: 'a>(
+    summary: &'a RunSummary,
+    settings: &'a Settings,
+) -> Element<'a, Message> {
+    let height = summary.results.len() as f32 * ROW_HEIGHT + AXIS_HEIGHT;
+    canvas::Canvas::new(Timeline { summary, settings })
+        .width(Length::Fill)
+        .height(height)
+        .into()
+}
+
+struct Timeline<'a> {
+    summary: &'a RunSummary,
+    settings: &'a Settings,
+}
+
+impl Timeline<'_> {
+    /// Milliseconds covered by the chart; never zero.
+    fn span_ms(&self) -> f32 {
+        let end = self
+            .summary
+            .results
+            .iter()
+            .map(|r| r.started + r.elapsed)
+            .max()
+            .unwrap_or_default()
+            .max(self.summary.total);
+        (end.as_secs_f32() * 1000.0).max(1.0)
+    }
+
+    fn describe(result: &RunResult) -> String {
+        let status = match &result.outcome {
+            Ok(status) => status.to_string(),
+            Err(e) => e.lines().next().unwrap_or_default().to_string(),
+        };
+        format!(
+            "{} · {status} · +{} ms · {} ms",
+            result.name,
+            result.started.as_millis(),
+            result.elapsed.as_millis()
+        )
+    }
+}
+
+impl<Message> canvas::Program<Message> for Timeline<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let label_color = Color::from_rgb8(200, 200, 200);
+        let track_width = (bounds.width - LABEL_WIDTH).max(1.0);
+        let span_ms = self.span_ms();
+        let x_for = |ms: f32| LABEL_WIDTH + ms / span_ms * track_width;
+
+        for (i, result) in self.summary.results.iter().enumerate() {
+            let y = i as f32 * ROW_HEIGHT;
+            frame.fill_text(Text {
+                content: result.name.chars().take(24).collect(),
+                position: Point::new(4.0, y + 4.0),
+                color: label_color,
+                size: Pixels(12.0),
+                ..Text::default()
+            });
+
+            let start = x_for(result.started.as_secs_f32() * 1000.0);
+            let width = (result.elapsed.as_secs_f32() * 1000.0 / span_ms * track_width).max(2.0);
+            let color = if result.outcome.is_ok() {
+                self.settings.timing_color(result.elapsed)
+            } else {
+                Color::from_rgb8(139, 139, 139)
+            };
+            frame.fill_rectangle(
+                Point::new(start, y + (ROW_HEIGHT - BAR_HEIGHT) / 2.0),
+                Size::new(width, BAR_HEIGHT),
+                color,
+            );
+        }
+
+        let axis_y = self.summary.results.len() as f32 * ROW_HEIGHT;
+        frame.fill_rectangle(
+            Point::new(LABEL_WIDTH, axis_y),
+            Size::new(track_width, 1.0),
+            label_color,
+        );
+        frame.fill_text(Text {
+            content: "0 ms".to_string(),
+            position: Point::new(LABEL_WIDTH, axis_y + 3.0),
+            color: label_color,
+            size: Pixels(11.0),
+            ..Text::default()
+        });
+        let end_label = format!("{} ms", span_ms.round());
+        frame.fill_text(Text {
+            content: end_label.clone(),
+            position: Point::new(
+                bounds.width - end_label.len() as f32 * 7.0 - 4.0,
+                axis_y + 3.0,
+            ),
+            color: label_color,
+            size: Pixels(11.0),
+            ..Text::default()
+        });
+
+        // Hover tooltip for the row under the cursor.
+        if let Some(position) = cursor.position_in(bounds) {
+            let row = (position.y / ROW_HEIGHT) as usize;
+            if let Some(result) = self.summary.results.get(row) {
+                let label = Self::describe(result);
+                let size = Size::new(label.chars().count() as f32 * 7.0 + 12.0, 20.0);
+                let x = position.x.min(bounds.width - size.width).max(0.0);
+                let y = if position.y + 16.0 + size.height > bounds.height {
+                    (position.y - size.height - 4.0).max(0.0)
+                } else {
+                    position.y + 16.0
+                };
+                frame.fill_rectangle(Point::new(x, y), size, Color::from_rgb8(40, 40, 40));
+                frame.fill_text(Text {
+                    content: label,
+                    position: Point::new(x + 6.0, y + 4.0),
+                    color: Color::WHITE,
+                    size: Pixels(12.0),
+                    ..Text::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Local, Utc};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// `"key": value` on a line of pretty-printed JSON.
+static FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*"([^"\\]*)":\s*("[^"\\]*"|-?\d+(?:\.\d+)?),?\s*$"#).unwrap()
+});
+
+/// Unix times outside 2000..2100 are more likely ids or counters.
+const MIN_UNIX: i64 = 946_684_800;
+const MAX_UNIX: i64 = 4_102_444_800;
+
+pub fn format_local(time: DateTime<Utc>) -> String {
+    format!(
+        "{} local",
+        time.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+    )
+}
+
+/// Appends `(2024-01-02 14:05 local)` to lines of pretty-printed JSON whose
+/// value looks like a timestamp: a number in a field named like `*_at`,
+/// `createdAt`, `timestamp`, `exp`..., or an ISO-8601 string in any field.
+pub fn annotate_timestamps(pretty: &str) -> String {
+    pretty
+        .lines()
+        .map(|line| match field_time(line) {
+            Some(time) => format!("{line}  ({})", format_local(time)),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Converts an HTTP `Date`-style header value to local time.
+pub fn http_date_local(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|time| format_local(time.with_timezone(&Utc)))
+}
+
+fn field_time(line: &str) -> Option<DateTime<Utc>> {
+    let captures = FIELD.captures(line)?;
+    let key = &captures[1];
+    let value = &captures[2];
+    if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        return DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|time| time.with_timezone(&Utc));
+    }
+    if !is_time_key(key) {
+        return None;
+    }
+    let number = value.parse::<f64>().ok()?;
+    // Millisecond timestamps are three orders of magnitude larger.
+    let millis = if number.abs() >= MAX_UNIX as f64 {
+        number
+    } else {
+        number * 1000.0
+    };
+    let seconds = (millis / 1000.0) as i64;
+    if !(MIN_UNIX..MAX_UNIX).contains(&seconds) {
+        return None;
+    }
+    DateTime::from_timestamp_millis(millis as i64)
+}
+
+fn is_time_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    lower.ends_with("_at")
+        || key.ends_with("At")
+        || lower.contains("timestamp")
+        || lower.ends_with("time")
+        || matches!(lower.as_str(), "exp" | "iat" | "nbf" | "ts")
+}
@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+/// Oldest steps are dropped beyond this.
+const MAX_STEPS: usize = 100;
+
+/// Edits closer together than this are merged into a single undo step.
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Snapshot-based undo/redo history for a text buffer.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    undo: Vec<String>,
+    redo: Vec<String>,
+    last_edit: Option<Instant>,
+}
+
+impl UndoStack {
+    /// Records the text as it was before an edit. Bursts of typing become
+    /// one step; `force` starts a new step regardless (paste, new line,
+    /// programmatic replacement).
+    pub fn record(&mut self, before: &str, force: bool) {
+        let now = Instant::now();
+        let new_step = force || self.last_edit.is_none_or(|last| now - last > DEBOUNCE);
+        self.last_edit = Some(now);
+        if !new_step || self.undo.last().is_some_and(|top| top == before) {
+            return;
+        }
+        self.undo.push(before.to_string());
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the text to restore, remembering `current` for redo.
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current.to_string());
+        self.last_edit = None;
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: &str) -> Option<String> {
+        let next = self.redo.pop()?;
+        self.undo.push(current.to_string());
+        self.last_edit = None;
+        Some(next)
+    }
+}